@@ -0,0 +1,120 @@
+use nalgebra::{Point2, Vector2};
+
+/// An axis-aligned bounding box in world space.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    /// The corner with the smallest coordinates.
+    pub min: Point2<f64>,
+    /// The corner with the largest coordinates.
+    pub max: Point2<f64>,
+}
+
+impl Aabb {
+    /// Creates a new bounding box from its corners.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - The corner with the smallest coordinates.
+    /// * `max` - The corner with the largest coordinates.
+    pub fn new(min: Point2<f64>, max: Point2<f64>) -> Self {
+        Aabb { min, max }
+    }
+
+    /// Creates the smallest bounding box containing all of the given points.
+    ///
+    /// # Returns
+    ///
+    /// The bounding box, or `None` if there are no points.
+    pub fn from_points<I: IntoIterator<Item = Point2<f64>>>(points: I) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+
+        Some(points.fold(Aabb::new(first, first), |aabb, p| Aabb {
+            min: aabb.min.inf(&p),
+            max: aabb.max.sup(&p),
+        }))
+    }
+
+    /// Returns `true` if the two boxes overlap or touch.
+    pub fn overlaps(&self, other: &Aabb) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+    }
+
+    /// Returns `true` if `other` lies entirely inside this box.
+    pub fn contains(&self, other: &Aabb) -> bool {
+        self.min.x <= other.min.x
+            && self.min.y <= other.min.y
+            && other.max.x <= self.max.x
+            && other.max.y <= self.max.y
+    }
+
+    /// Returns `true` if the point lies inside or on the border of the box.
+    pub fn contains_point(&self, p: &Point2<f64>) -> bool {
+        self.min.x <= p.x && p.x <= self.max.x && self.min.y <= p.y && p.y <= self.max.y
+    }
+
+    /// Returns the smallest box containing both boxes.
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: self.min.inf(&other.min),
+            max: self.max.sup(&other.max),
+        }
+    }
+
+    /// Returns the box grown by `margin` on every side.
+    pub fn fattened(&self, margin: f64) -> Aabb {
+        let margin = Vector2::new(margin, margin);
+
+        Aabb {
+            min: self.min - margin,
+            max: self.max + margin,
+        }
+    }
+
+    /// Returns the perimeter of the box, which the dynamic tree uses as its cost metric.
+    pub fn perimeter(&self) -> f64 {
+        2.0 * ((self.max.x - self.min.x) + (self.max.y - self.min.y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlaps_and_contains() {
+        let a = Aabb::new(Point2::new(0.0, 0.0), Point2::new(2.0, 2.0));
+        let b = Aabb::new(Point2::new(2.0, 1.0), Point2::new(3.0, 3.0));
+        let c = Aabb::new(Point2::new(2.5, 0.0), Point2::new(3.0, 1.0));
+
+        assert!(a.overlaps(&b));
+        assert!(!a.overlaps(&c));
+        assert!(a.fattened(0.5).overlaps(&c));
+        assert!(a.fattened(0.1).contains(&a));
+        assert!(!a.contains(&b));
+        assert_eq!(
+            a.union(&c),
+            Aabb::new(Point2::new(0.0, 0.0), Point2::new(3.0, 2.0))
+        );
+        assert_eq!(a.perimeter(), 8.0);
+    }
+
+    #[test]
+    fn test_from_points() {
+        let aabb = Aabb::from_points([
+            Point2::new(1.0, -1.0),
+            Point2::new(-2.0, 0.5),
+            Point2::new(0.0, 3.0),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            aabb,
+            Aabb::new(Point2::new(-2.0, -1.0), Point2::new(1.0, 3.0))
+        );
+        assert!(Aabb::from_points(std::iter::empty()).is_none());
+    }
+}
@@ -0,0 +1,298 @@
+use std::ops;
+
+/// A generational index into an `Arena`.
+///
+/// The generation is bumped every time a slot is freed, so an index held on to after its value
+/// was removed will no longer resolve, even if the slot has since been reused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Index {
+    /// The slot in the arena.
+    slot: u32,
+    /// The generation of the slot when the value was inserted.
+    generation: u32,
+}
+
+impl Index {
+    /// Returns the slot of the index.
+    ///
+    /// Slots are dense and reused, which makes them suitable for indexing side tables.
+    pub fn slot(&self) -> usize {
+        self.slot as usize
+    }
+
+    /// Returns the generation of the index.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Creates an index without an arena, for tests that only need distinct indices.
+    #[cfg(test)]
+    pub(crate) fn from_raw(slot: u32, generation: u32) -> Self {
+        Index { slot, generation }
+    }
+}
+
+/// A single slot of an `Arena`.
+enum Entry<T> {
+    /// The slot holds a live value.
+    Occupied { generation: u32, value: T },
+    /// The slot is free and remembers the generation of its last value.
+    Free { generation: u32 },
+}
+
+/// A vector backed store that hands out generational `Index`es instead of references.
+pub struct Arena<T> {
+    /// All slots, live or free.
+    entries: Vec<Entry<T>>,
+    /// Slots that can be reused by the next insertion.
+    free: Vec<u32>,
+    /// Number of live values.
+    len: usize,
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Arena<T> {
+    /// Creates a new, empty arena.
+    pub fn new() -> Self {
+        Arena {
+            entries: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of live values in the arena.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the arena holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts a value into the arena.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to insert.
+    ///
+    /// # Returns
+    ///
+    /// The index of the value.
+    pub fn insert(&mut self, value: T) -> Index {
+        self.len += 1;
+
+        if let Some(slot) = self.free.pop() {
+            let entry = &mut self.entries[slot as usize];
+            let generation = match entry {
+                Entry::Free { generation } => *generation,
+                Entry::Occupied { .. } => unreachable!("free list points at a live slot"),
+            };
+
+            *entry = Entry::Occupied { generation, value };
+            return Index { slot, generation };
+        }
+
+        let slot = self.entries.len() as u32;
+        self.entries.push(Entry::Occupied {
+            generation: 0,
+            value,
+        });
+
+        Index {
+            slot,
+            generation: 0,
+        }
+    }
+
+    /// Removes a value from the arena.
+    ///
+    /// # Arguments
+    ///
+    /// * `idx` - The index of the value to remove.
+    ///
+    /// # Returns
+    ///
+    /// The removed value, or `None` if the index is stale.
+    pub fn remove(&mut self, idx: Index) -> Option<T> {
+        let entry = self.entries.get_mut(idx.slot())?;
+
+        match entry {
+            Entry::Occupied { generation, .. } if *generation == idx.generation => {
+                let next = Entry::Free {
+                    generation: generation.wrapping_add(1),
+                };
+
+                self.free.push(idx.slot);
+                self.len -= 1;
+
+                match std::mem::replace(entry, next) {
+                    Entry::Occupied { value, .. } => Some(value),
+                    Entry::Free { .. } => unreachable!(),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the index refers to a live value.
+    pub fn contains(&self, idx: Index) -> bool {
+        self.get(idx).is_some()
+    }
+
+    /// Returns a reference to the value at the given index.
+    pub fn get(&self, idx: Index) -> Option<&T> {
+        match self.entries.get(idx.slot())? {
+            Entry::Occupied { generation, value } if *generation == idx.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value at the given index.
+    pub fn get_mut(&mut self, idx: Index) -> Option<&mut T> {
+        match self.entries.get_mut(idx.slot())? {
+            Entry::Occupied { generation, value } if *generation == idx.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns mutable references to two distinct values at once.
+    ///
+    /// # Returns
+    ///
+    /// `None` if either index is stale or both refer to the same slot.
+    pub fn get2_mut(&mut self, a: Index, b: Index) -> Option<(&mut T, &mut T)> {
+        if a.slot == b.slot || !self.contains(a) || !self.contains(b) {
+            return None;
+        }
+
+        let (first, second) = if a.slot < b.slot { (a, b) } else { (b, a) };
+        let (head, tail) = self.entries.split_at_mut(second.slot());
+
+        let first_value = match &mut head[first.slot()] {
+            Entry::Occupied { value, .. } => value,
+            Entry::Free { .. } => unreachable!(),
+        };
+        let second_value = match &mut tail[0] {
+            Entry::Occupied { value, .. } => value,
+            Entry::Free { .. } => unreachable!(),
+        };
+
+        if a.slot < b.slot {
+            Some((first_value, second_value))
+        } else {
+            Some((second_value, first_value))
+        }
+    }
+
+    /// Iterates over all live values and their indices, in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = (Index, &T)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, entry)| match entry {
+                Entry::Occupied { generation, value } => Some((
+                    Index {
+                        slot: slot as u32,
+                        generation: *generation,
+                    },
+                    value,
+                )),
+                Entry::Free { .. } => None,
+            })
+    }
+
+    /// Iterates mutably over all live values and their indices, in slot order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Index, &mut T)> {
+        self.entries
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(slot, entry)| match entry {
+                Entry::Occupied { generation, value } => Some((
+                    Index {
+                        slot: slot as u32,
+                        generation: *generation,
+                    },
+                    value,
+                )),
+                Entry::Free { .. } => None,
+            })
+    }
+}
+
+impl<T> ops::Index<Index> for Arena<T> {
+    type Output = T;
+
+    /// Returns a reference to the value at the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is stale.
+    fn index(&self, idx: Index) -> &T {
+        self.get(idx).expect("stale arena index")
+    }
+}
+
+impl<T> ops::IndexMut<Index> for Arena<T> {
+    /// Returns a mutable reference to the value at the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is stale.
+    fn index_mut(&mut self, idx: Index) -> &mut T {
+        self.get_mut(idx).expect("stale arena index")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_get_remove() {
+        let mut arena = Arena::new();
+        let a = arena.insert("a");
+        let b = arena.insert("b");
+
+        assert_eq!(arena.len(), 2);
+        assert_eq!(arena.get(a), Some(&"a"));
+        assert_eq!(arena.remove(a), Some("a"));
+        assert_eq!(arena.get(a), None);
+        assert_eq!(arena.remove(a), None);
+        assert_eq!(arena.get(b), Some(&"b"));
+        assert_eq!(arena.len(), 1);
+    }
+
+    #[test]
+    fn test_stale_index_after_reuse() {
+        let mut arena = Arena::new();
+        let a = arena.insert(1);
+        arena.remove(a);
+        let b = arena.insert(2);
+
+        assert_eq!(a.slot(), b.slot());
+        assert_ne!(a, b);
+        assert_eq!(arena.get(a), None);
+        assert_eq!(arena.get(b), Some(&2));
+    }
+
+    #[test]
+    fn test_get2_mut() {
+        let mut arena = Arena::new();
+        let a = arena.insert(1);
+        let b = arena.insert(2);
+
+        let (x, y) = arena.get2_mut(b, a).unwrap();
+        assert_eq!((*x, *y), (2, 1));
+        *x += 10;
+
+        assert_eq!(arena.get(b), Some(&12));
+        assert!(arena.get2_mut(a, a).is_none());
+    }
+}
@@ -0,0 +1,62 @@
+use crate::aabb::Aabb;
+use crate::scene::BodyHandle;
+
+/// Finds the pairs of bodies that might be touching, so that the narrow phase only has to look at
+/// those.
+///
+/// A `Scene` keeps its broad phase up to date by calling `insert` and `remove` as bodies come and
+/// go, and `update` for every body at the end of each step. Bodies can also be moved between
+/// steps through the scene's mutable accessors, in which case every body is updated again right
+/// before the next call to `pairs`. Either way, the boxes are current whenever `pairs` is called;
+/// the scene does not call `query` while they are out of date.
+pub trait BroadPhase: Send {
+    /// Starts tracking a body.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the body.
+    /// * `aabb` - The world space bounding box of the body.
+    fn insert(&mut self, handle: BodyHandle, aabb: Aabb);
+
+    /// Stops tracking a body.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the body.
+    fn remove(&mut self, handle: BodyHandle);
+
+    /// Updates the bounding box of a tracked body.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the body.
+    /// * `aabb` - The new world space bounding box of the body.
+    fn update(&mut self, handle: BodyHandle, aabb: Aabb);
+
+    /// Returns the candidate pairs of bodies.
+    ///
+    /// Every pair of bodies whose bounding boxes overlap must be included; pairs that do not
+    /// overlap may be included too.
+    ///
+    /// # Returns
+    ///
+    /// The candidate pairs, each with the lower handle first and the whole list sorted, so that
+    /// the result does not depend on the strategy's internal order.
+    fn pairs(&mut self) -> Vec<(BodyHandle, BodyHandle)>;
+
+    /// Returns the bodies that might overlap a box.
+    ///
+    /// Every body whose bounding box, as of the last `update`, overlaps the given box must be
+    /// included; others may be included too.
+    ///
+    /// # Arguments
+    ///
+    /// * `aabb` - The world space box to look in.
+    fn query(&self, aabb: &Aabb) -> Vec<BodyHandle>;
+}
+
+/// Returns `count` distinct handles for testing broad phases without a scene.
+#[cfg(test)]
+pub(crate) fn test_handles(count: u32) -> Vec<BodyHandle> {
+    (0..count).map(BodyHandle::from_slot).collect()
+}
@@ -0,0 +1,53 @@
+use std::collections::BTreeSet;
+
+use crate::aabb::Aabb;
+use crate::broad_phase::BroadPhase;
+use crate::scene::BodyHandle;
+
+/// A broad phase that reports every pair of bodies.
+///
+/// This is what the scene did before it had a broad phase. It is far too slow for large scenes,
+/// but makes a useful reference to check the other strategies against.
+#[derive(Default)]
+pub struct BruteForce {
+    /// The tracked bodies, in handle order.
+    handles: BTreeSet<BodyHandle>,
+}
+
+impl BruteForce {
+    /// Creates a new, empty brute force broad phase.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl BroadPhase for BruteForce {
+    /// Starts tracking a body.
+    fn insert(&mut self, handle: BodyHandle, _aabb: Aabb) {
+        self.handles.insert(handle);
+    }
+
+    /// Stops tracking a body.
+    fn remove(&mut self, handle: BodyHandle) {
+        self.handles.remove(&handle);
+    }
+
+    /// Does nothing, as bounding boxes are not used.
+    fn update(&mut self, _handle: BodyHandle, _aabb: Aabb) {}
+
+    /// Returns every pair of tracked bodies.
+    fn pairs(&mut self) -> Vec<(BodyHandle, BodyHandle)> {
+        let handles: Vec<BodyHandle> = self.handles.iter().copied().collect();
+
+        handles
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &a)| handles[i + 1..].iter().map(move |&b| (a, b)))
+            .collect()
+    }
+
+    /// Returns every tracked body.
+    fn query(&self, _aabb: &Aabb) -> Vec<BodyHandle> {
+        self.handles.iter().copied().collect()
+    }
+}
@@ -1,10 +1,19 @@
-use graphics::{ellipse, Context};
+#[cfg(feature = "render-piston")]
+use graphics::{ellipse, line::Line, Context};
+#[cfg(feature = "render-piston")]
 use opengl_graphics::GlGraphics;
 
+use nalgebra::{Point2, Vector2};
+
+use ordered_float::OrderedFloat;
 use std::f64::consts::PI;
 
+use crate::aabb::Aabb;
+#[cfg(feature = "render-piston")]
 use crate::constants::WHITE;
 use crate::mass_data::MassData;
+use crate::ray::Ray;
+use crate::shape_error::ShapeError;
 use crate::shapes::{Shape, ShapeDiscriminant};
 use crate::transform::Transform;
 use crate::types::{KilogramPerCubicMeter, Meter};
@@ -15,6 +24,57 @@ pub struct Circle {
     pub radius: Meter,
 }
 
+impl Circle {
+    /// Creates a new circle with the given radius.
+    ///
+    /// # Arguments
+    ///
+    /// * `radius` - The radius of the circle, in meters.
+    ///
+    /// # Returns
+    ///
+    /// A new `Circle`, or a `ShapeError` if the radius is not a positive finite number.
+    pub fn new(radius: f64) -> Result<Self, ShapeError> {
+        if !radius.is_finite() {
+            return Err(ShapeError::NonFiniteRadius(radius));
+        }
+
+        if radius <= 0.0 {
+            return Err(ShapeError::NonPositiveRadius(radius));
+        }
+
+        Ok(Circle {
+            radius: OrderedFloat(radius),
+        })
+    }
+
+    /// Finds the support point of the circle in the given direction.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The direction vector, in model space.
+    ///
+    /// # Returns
+    ///
+    /// The point on the circle furthest along the direction, in model space. Every point is
+    /// equally far along a zero direction, so the one on the positive x axis is returned.
+    pub fn find_support(&self, dir: &Vector2<f64>) -> Point2<f64> {
+        let dir = dir.try_normalize(f64::EPSILON).unwrap_or(Vector2::x());
+
+        Point2::from(dir * *self.radius)
+    }
+
+    /// Returns `true` if a world space point lies inside or on the circle.
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - The point, in world space.
+    /// * `tx` - The transformation to apply to the shape.
+    pub fn contains_point(&self, p: &Point2<f64>, tx: &Transform) -> bool {
+        (p - tx.pos).norm_squared() <= *self.radius * *self.radius
+    }
+}
+
 impl Shape for Circle {
     /// Calculates the mass and moment of inertia of the circle.
     ///
@@ -27,7 +87,62 @@ impl Shape for Circle {
     /// A `MassData` struct containing the mass and moment of inertia.
     fn calculate_mass_data(&mut self, density: KilogramPerCubicMeter) -> MassData {
         let m = PI * *self.radius * *self.radius * density;
-        MassData::new(m, m * *self.radius * *self.radius)
+        // A solid disc
+        MassData::new(m, 0.5 * m * *self.radius * *self.radius)
+    }
+
+    /// Returns the centroid of the circle, which is always its center.
+    fn centroid(&self) -> Point2<f64> {
+        Point2::origin()
+    }
+
+    /// Calculates the world space bounding box of the circle.
+    ///
+    /// # Parameters
+    ///
+    /// * `tx`: The transformation to apply to the shape.
+    fn bounding_box(&self, tx: &Transform) -> Aabb {
+        let r = Vector2::new(*self.radius, *self.radius);
+
+        Aabb::new(tx.pos - r, tx.pos + r)
+    }
+
+    /// Finds where a ray first enters the circle.
+    ///
+    /// A ray starting inside the circle does not hit it.
+    ///
+    /// # Arguments
+    ///
+    /// * `ray` - The ray, in world space.
+    /// * `tx` - The transformation to apply to the shape.
+    ///
+    /// # Returns
+    ///
+    /// The distance along the ray and the world space normal at the hit point, or `None` if the
+    /// ray misses within its maximum distance.
+    fn ray_cast(&self, ray: &Ray, tx: &Transform) -> Option<(f64, Vector2<f64>)> {
+        let m = ray.origin - tx.pos;
+        let b = m.dot(&ray.dir);
+        let c = m.norm_squared() - *self.radius * *self.radius;
+
+        // Starting inside, or outside and pointing away
+        if c <= 0.0 || b > 0.0 {
+            return None;
+        }
+
+        let disc = b * b - c;
+        if disc < 0.0 {
+            return None;
+        }
+
+        let distance = -b - disc.sqrt();
+        if distance > ray.max_distance {
+            return None;
+        }
+
+        let normal = (ray.point_at(distance) - tx.pos) / *self.radius;
+
+        Some((distance, normal))
     }
 
     /// Draws the circle on the screen.
@@ -37,6 +152,7 @@ impl Shape for Circle {
     /// * `c`: The graphics context.
     /// * `gl`: The OpenGL graphics context.
     /// * `tx`: The transformation to apply to the shape.
+    #[cfg(feature = "render-piston")]
     fn draw(&self, c: Context, gl: &mut GlGraphics, tx: &Transform) {
         ellipse::Ellipse::new_border(WHITE, 1.0).draw(
             ellipse::circle(tx.pos.x, tx.pos.y, *self.radius),
@@ -44,6 +160,15 @@ impl Shape for Circle {
             c.transform,
             gl,
         );
+
+        // Draw a radius so the rotation of the circle is visible
+        let edge = tx.to_world(&Point2::new(*self.radius, 0.0));
+        Line::new(WHITE, 1.0).draw(
+            [tx.pos.x, tx.pos.y, edge.x, edge.y],
+            &c.draw_state,
+            c.transform,
+            gl,
+        );
     }
 
     /// Returns the unique identifier for the circle shape.
@@ -55,3 +180,72 @@ impl Shape for Circle {
         ShapeDiscriminant::Circle
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_support() {
+        let circle = Circle::new(2.0).unwrap();
+
+        assert!(
+            (circle.find_support(&Vector2::new(3.0, 4.0)) - Point2::new(1.2, 1.6)).norm() < 1e-12
+        );
+        assert_eq!(
+            circle.find_support(&Vector2::zeros()),
+            Point2::new(2.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_contains_point() {
+        let circle = Circle::new(1.0).unwrap();
+        let tx = Transform::new(Point2::new(3.0, 1.0));
+
+        assert!(circle.contains_point(&Point2::new(3.5, 1.5), &tx));
+        assert!(circle.contains_point(&Point2::new(4.0, 1.0), &tx));
+        assert!(!circle.contains_point(&Point2::new(3.8, 1.8), &tx));
+    }
+
+    #[test]
+    fn test_ray_cast() {
+        let circle = Circle::new(1.0).unwrap();
+        let tx = Transform::new(Point2::new(3.0, 1.0));
+
+        let ray = Ray::new(Point2::new(0.0, 1.0), Vector2::x(), 10.0).unwrap();
+        let (distance, normal) = circle.ray_cast(&ray, &tx).unwrap();
+        assert!((distance - 2.0).abs() < 1e-12);
+        assert!((normal - Vector2::new(-1.0, 0.0)).norm() < 1e-12);
+
+        // Too short, pointing away, passing by and starting inside
+        let short = Ray::new(Point2::new(0.0, 1.0), Vector2::x(), 1.5).unwrap();
+        let away = Ray::new(Point2::new(0.0, 1.0), -Vector2::x(), 10.0).unwrap();
+        let past = Ray::new(Point2::new(0.0, 2.5), Vector2::x(), 10.0).unwrap();
+        let inside = Ray::new(Point2::new(3.0, 1.5), Vector2::x(), 10.0).unwrap();
+        for ray in [short, away, past, inside] {
+            assert!(circle.ray_cast(&ray, &tx).is_none());
+        }
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(Circle::new(2.0).unwrap().radius, OrderedFloat(2.0));
+        assert_eq!(
+            Circle::new(0.0).err(),
+            Some(ShapeError::NonPositiveRadius(0.0))
+        );
+        assert_eq!(
+            Circle::new(-1.0).err(),
+            Some(ShapeError::NonPositiveRadius(-1.0))
+        );
+        assert_eq!(
+            Circle::new(f64::INFINITY).err(),
+            Some(ShapeError::NonFiniteRadius(f64::INFINITY))
+        );
+        assert!(matches!(
+            Circle::new(f64::NAN),
+            Err(ShapeError::NonFiniteRadius(_))
+        ));
+    }
+}
@@ -1,23 +1,28 @@
-use nalgebra::{distance_squared, Point2, Vector2};
+use nalgebra::{distance_squared, Vector2};
 use ordered_float::OrderedFloat;
-use std::f64::{EPSILON, INFINITY, NEG_INFINITY};
 
+use crate::constants::REFERENCE_FACE_TOLERANCE;
 use crate::custom_math::bias_gt;
+use crate::epa::penetration;
 use crate::manifold::Manifold;
+use crate::object::Object;
 use crate::polygon::Polygon;
-use crate::shapes::Shapes;
+use crate::shapes::ConvexPart;
+use crate::support_map::SupportMap;
+use crate::transform::Transform;
 
 /// Handles collision between two circles and updates the manifold.
 ///
 /// # Arguments
 ///
 /// * `manifold` - A mutable reference to the collision manifold.
-pub fn circle_circle(manifold: &mut Manifold) {
-    let a = manifold.a.borrow();
-    let b = manifold.b.borrow();
+/// * `a` - The object referred to by `manifold.a`.
+/// * `b` - The object referred to by `manifold.b`.
+pub fn circle_circle(manifold: &mut Manifold, a: &Object, b: &Object) {
+    let ((part_a, a_tx), (part_b, b_tx)) = (a.part(manifold.part_a), b.part(manifold.part_b));
 
-    if let (Shapes::Circle(c1), Shapes::Circle(c2)) = (&a.shape, &b.shape) {
-        let norm = b.tx.pos - a.tx.pos;
+    if let (ConvexPart::Circle(c1), ConvexPart::Circle(c2)) = (part_a, part_b) {
+        let norm = b_tx.pos - a_tx.pos;
         let dist_sqr = norm.norm_squared();
         let rad = c1.radius + c2.radius;
 
@@ -32,11 +37,11 @@ pub fn circle_circle(manifold: &mut Manifold) {
         if dist == 0.0 {
             manifold.penetration = c1.radius;
             manifold.normal = Vector2::new(1.0, 0.0);
-            manifold.contacts[0] = a.tx.pos.coords;
+            manifold.contacts[0] = a_tx.pos.coords;
         } else {
             manifold.penetration = rad - dist;
             manifold.normal = norm / dist;
-            manifold.contacts[0] = manifold.normal * *c2.radius + a.tx.pos.coords;
+            manifold.contacts[0] = manifold.normal * *c2.radius + a_tx.pos.coords;
         }
     }
 }
@@ -46,48 +51,53 @@ pub fn circle_circle(manifold: &mut Manifold) {
 /// # Arguments
 ///
 /// * `manifold` - A mutable reference to the collision manifold.
+/// * `a` - The object referred to by `manifold.a`.
+/// * `b` - The object referred to by `manifold.b`.
 /// * `circle_first` - A boolean indicating whether the circle is the first shape in the collision check.
-pub fn circle_polygon(manifold: &mut Manifold, circle_first: bool) {
-    let (a, b) = if circle_first {
-        (manifold.a.borrow(), manifold.b.borrow())
+pub fn circle_polygon(manifold: &mut Manifold, a: &Object, b: &Object, circle_first: bool) {
+    let ((part_a, a_tx), (part_b, b_tx)) = if circle_first {
+        (a.part(manifold.part_a), b.part(manifold.part_b))
     } else {
-        (manifold.b.borrow(), manifold.a.borrow())
+        (b.part(manifold.part_b), a.part(manifold.part_a))
     };
 
-    if let (Shapes::Circle(c), Shapes::Polygon(p)) = (&a.shape, &b.shape) {
+    if let (ConvexPart::Circle(c), ConvexPart::Polygon(p)) = (part_a, part_b) {
         manifold.contact_count = 0;
 
         // Transform circle to polygon model space
-        let cen: Point2<f64> = (p.orient.transpose() * (a.tx.pos - b.tx.pos)).into();
+        let cen = b_tx.to_local(&a_tx.pos);
 
         // Find min penetration edge
-        let (face_norm, separation) = p.vertices.iter().enumerate().fold(
-            (0, NEG_INFINITY),
-            |(face_norm, separation), (i, vertex)| {
-                let s = p.normals[i].dot(&(cen - vertex));
-                if s > *c.radius {
-                    (face_norm, separation)
-                } else if s > separation {
-                    (i, -s)
-                } else {
-                    (face_norm, separation)
-                }
-            },
-        );
+        let mut separation = f64::NEG_INFINITY;
+        let mut face_norm = 0;
 
-        if separation > *c.radius {
-            return;
+        for (i, vertex) in p.vertices.iter().enumerate() {
+            let s = p.normals[i].dot(&(cen - vertex));
+
+            if s > *c.radius {
+                return;
+            }
+
+            if s > separation {
+                separation = s;
+                face_norm = i;
+            }
         }
 
         // Get the face's verts
         let v1 = p.vertices[face_norm];
         let v2 = p.vertices[(face_norm + 1) % p.vertices.len()];
 
+        // The normal is found pointing from the circle to the polygon, so it must be flipped when
+        // the polygon is A
+        let sign = if circle_first { 1.0 } else { -1.0 };
+
         // Check if cen is in polygon
-        if separation < EPSILON {
+        if separation < f64::EPSILON {
+            let norm = -b_tx.rotate_to_world(&p.normals[face_norm]);
             manifold.contact_count = 1;
-            manifold.normal = -(p.orient * p.normals[face_norm]);
-            manifold.contacts[0] = manifold.normal * *c.radius + a.tx.pos.coords;
+            manifold.normal = norm * sign;
+            manifold.contacts[0] = norm * *c.radius + a_tx.pos.coords;
             manifold.penetration = c.radius;
             return;
         }
@@ -111,21 +121,20 @@ pub fn circle_polygon(manifold: &mut Manifold, circle_first: bool) {
             }
 
             manifold.contact_count = 1;
-            let mut norm = vertex - cen;
-            norm = p.orient * norm;
-            manifold.normal = norm.normalize();
-            manifold.contacts[0] = p.orient * vertex.coords + b.tx.pos.coords;
+            let norm = b_tx.rotate_to_world(&(vertex - cen));
+            manifold.normal = norm.normalize() * sign;
+            manifold.contacts[0] = b_tx.to_world(&vertex).coords;
         } else {
             // Near face
-            let mut norm = p.normals[face_norm];
+            let norm = p.normals[face_norm];
             if (cen - v1).dot(&norm) > *c.radius {
                 return;
             }
 
+            let norm = -b_tx.rotate_to_world(&norm);
             manifold.contact_count = 1;
-            norm = p.orient * norm;
-            manifold.normal = -norm;
-            manifold.contacts[0] = manifold.normal * *c.radius + a.tx.pos.coords;
+            manifold.normal = norm * sign;
+            manifold.contacts[0] = norm * *c.radius + a_tx.pos.coords;
         }
     }
 }
@@ -136,57 +145,46 @@ pub fn circle_polygon(manifold: &mut Manifold, circle_first: bool) {
 /// # Arguments
 ///
 /// * `manifold` - A mutable reference to the collision manifold.
-pub fn polygon_polygon(manifold: &mut Manifold) {
-    let a = manifold.a.borrow();
-    let b = manifold.b.borrow();
+/// * `a` - The object referred to by `manifold.a`.
+/// * `b` - The object referred to by `manifold.b`.
+pub fn polygon_polygon(manifold: &mut Manifold, a: &Object, b: &Object) {
+    let ((part_a, a_tx), (part_b, b_tx)) = (a.part(manifold.part_a), b.part(manifold.part_b));
 
-    if let (Shapes::Polygon(p1), Shapes::Polygon(p2)) = (&a.shape, &b.shape) {
+    if let (ConvexPart::Polygon(p1), ConvexPart::Polygon(p2)) = (part_a, part_b) {
         manifold.contact_count = 0;
 
-        let (pen_a, face_a) = find_axis_least_pen(p1, p2, a.tx.pos, b.tx.pos);
+        let (pen_a, face_a) = find_axis_least_pen(p1, &a_tx, p2, &b_tx);
         if pen_a >= 0.0 {
             return;
         }
-        let (pen_b, face_b) = find_axis_least_pen(p2, p1, a.tx.pos, b.tx.pos);
+        let (pen_b, face_b) = find_axis_least_pen(p2, &b_tx, p1, &a_tx);
         if pen_b >= 0.0 {
             return;
         }
 
-        let (mut ref_idx, flip, ref_poly, inc_poly) = if bias_gt(pen_a, pen_b) {
-            (face_a, false, p1, p2)
+        let (mut ref_idx, flip, ref_poly, ref_tx, inc_poly, inc_tx) = if bias_gt(pen_a, pen_b) {
+            (face_a, false, p1, &a_tx, p2, &b_tx)
         } else {
-            (face_b, true, p2, p1)
+            (face_b, true, p2, &b_tx, p1, &a_tx)
         };
 
         let mut incident_face = [Vector2::zeros(); 2];
         find_incident_face(
             &mut incident_face,
             ref_poly,
+            ref_tx,
             inc_poly,
+            inc_tx,
             ref_idx,
-            if flip { a.tx.pos } else { b.tx.pos },
         );
 
-        let mut v1 = ref_poly.vertices[ref_idx];
+        let v1 = ref_tx.to_world(&ref_poly.vertices[ref_idx]);
         ref_idx = if ref_idx + 1 == ref_poly.vertices.len() {
             0
         } else {
             ref_idx + 1
         };
-        let mut v2 = ref_poly.vertices[ref_idx];
-
-        v1 = ref_poly.orient * v1
-            + if flip {
-                b.tx.pos.coords
-            } else {
-                a.tx.pos.coords
-            };
-        v2 = ref_poly.orient * v2
-            + if flip {
-                b.tx.pos.coords
-            } else {
-                a.tx.pos.coords
-            };
+        let v2 = ref_tx.to_world(&ref_poly.vertices[ref_idx]);
 
         let side_plan_norm = (v2 - v1).normalize();
         let ref_face_norm = Vector2::new(side_plan_norm.y, -side_plan_norm.x);
@@ -226,34 +224,149 @@ pub fn polygon_polygon(manifold: &mut Manifold) {
     }
 }
 
+/// Handles collision between any two convex parts through the generic GJK/EPA path and updates
+/// the manifold.
+///
+/// # Arguments
+///
+/// * `manifold` - A mutable reference to the collision manifold.
+/// * `a` - The object referred to by `manifold.a`.
+/// * `b` - The object referred to by `manifold.b`.
+pub fn convex_convex(manifold: &mut Manifold, a: &Object, b: &Object) {
+    let ((part_a, a_tx), (part_b, b_tx)) = (a.part(manifold.part_a), b.part(manifold.part_b));
+
+    convex_manifold(manifold, &part_a, &a_tx, &part_b, &b_tx);
+}
+
+/// Builds the contact manifold of two convex shapes known only through their support functions.
+///
+/// EPA provides the normal and depth. The edges of both shapes facing along the normal are then
+/// found, and the one lying flatter against the normal becomes the reference face that the
+/// other is clipped against, as in `polygon_polygon`. Without two edges to clip, the single
+/// deepest point is used instead.
+///
+/// # Arguments
+///
+/// * `manifold` - A mutable reference to the collision manifold.
+/// * `a` - The first shape.
+/// * `a_tx` - The transform that brings the first shape into world space.
+/// * `b` - The second shape.
+/// * `b_tx` - The transform that brings the second shape into world space.
+pub fn convex_manifold<A, B>(
+    manifold: &mut Manifold,
+    a: &A,
+    a_tx: &Transform,
+    b: &B,
+    b_tx: &Transform,
+) where
+    A: SupportMap + ?Sized,
+    B: SupportMap + ?Sized,
+{
+    manifold.contact_count = 0;
+
+    let Some((normal, depth)) = penetration(a, a_tx, b, b_tx) else {
+        return;
+    };
+
+    manifold.normal = normal;
+    manifold.penetration = OrderedFloat(depth);
+
+    let edge_a = a.support_edge(a_tx, &normal);
+    let edge_b = b.support_edge(b_tx, &-normal);
+
+    let (reference, incident, flip) = match (edge_a, edge_b) {
+        (Some(ea), Some(eb)) => {
+            // The sine of the angle between each edge and the face perpendicular to the normal
+            let tilt_a = (ea.1 - ea.0).normalize().dot(&normal).abs();
+            let tilt_b = (eb.1 - eb.0).normalize().dot(&normal).abs();
+
+            if tilt_a <= tilt_b + REFERENCE_FACE_TOLERANCE {
+                (ea, eb, false)
+            } else {
+                (eb, ea, true)
+            }
+        }
+        (Some(_), None) => {
+            // Only `b` can be incident, and it touches at a single point
+            manifold.contacts[0] = b.support(b_tx, &-normal).coords;
+            manifold.contact_count = 1;
+            return;
+        }
+        (None, _) => {
+            manifold.contacts[0] = a.support(a_tx, &normal).coords;
+            manifold.contact_count = 1;
+            return;
+        }
+    };
+
+    let (v1, v2) = reference;
+    let side_plan_norm = (v2 - v1).normalize();
+    let ref_face_norm = Vector2::new(side_plan_norm.y, -side_plan_norm.x);
+    let ref_c = ref_face_norm.dot(&v1.coords);
+    let neg_side = -side_plan_norm.dot(&v1.coords);
+    let pos_side = side_plan_norm.dot(&v2.coords);
+
+    let mut incident_face = [incident.0.coords, incident.1.coords];
+    let clipped = clip(-side_plan_norm, neg_side, &mut incident_face) == 2
+        && clip(side_plan_norm, pos_side, &mut incident_face) == 2;
+
+    let mut cp = 0;
+    let mut total = 0.0;
+    if clipped {
+        for p in incident_face {
+            let separation = ref_face_norm.dot(&p) - ref_c;
+
+            if separation <= 0.0 {
+                manifold.contacts[cp] = p;
+                total -= separation;
+                cp += 1;
+            }
+        }
+    }
+
+    if cp == 0 {
+        // The clipped edge missed the reference face, so fall back on the deepest point of the
+        // incident shape
+        manifold.contacts[0] = if flip {
+            a.support(a_tx, &normal).coords
+        } else {
+            b.support(b_tx, &-normal).coords
+        };
+        manifold.contact_count = 1;
+        return;
+    }
+
+    manifold.normal = if flip { -ref_face_norm } else { ref_face_norm };
+    manifold.penetration = OrderedFloat(total / cp as f64);
+    manifold.contact_count = cp;
+}
+
 /// Finds the axis of least penetration between two polygons.
 ///
 /// # Arguments
 ///
 /// * `a` - The first polygon.
+/// * `a_tx` - The transform of the first polygon.
 /// * `b` - The second polygon.
-/// * `a_pos` - The position of the first polygon.
-/// * `b_pos` - The position of the second polygon.
+/// * `b_tx` - The transform of the second polygon.
 ///
 /// # Returns
 ///
 /// A tuple containing the penetration depth and the index of the least penetrating face.
 fn find_axis_least_pen(
     a: &Polygon,
+    a_tx: &Transform,
     b: &Polygon,
-    a_pos: Point2<f64>,
-    b_pos: Point2<f64>,
+    b_tx: &Transform,
 ) -> (f64, usize) {
-    let mut best_dist = NEG_INFINITY;
+    let mut best_dist = f64::NEG_INFINITY;
     let mut best_idx = 0;
 
     for i in 0..a.vertices.len() {
-        let n = b.orient.transpose() * (a.orient * a.normals[i]);
+        // Bring a's face normal and vertex into b's model space
+        let n = b_tx.rotate_to_local(&a_tx.rotate_to_world(&a.normals[i]));
         let s = b.find_support(&-n);
-        let mut v = a.vertices[i];
-        v = a.orient * v + a_pos.coords;
-        v -= b_pos.coords;
-        v = b.orient.transpose() * v;
+        let v = b_tx.to_local(&a_tx.to_world(&a.vertices[i]));
 
         let d = n.dot(&(s - v));
 
@@ -272,23 +385,23 @@ fn find_axis_least_pen(
 ///
 /// * `v` - A mutable array to store the incident face vertices.
 /// * `ref_poly` - The reference polygon.
+/// * `ref_tx` - The transform of the reference polygon.
 /// * `inc_poly` - The incident polygon.
+/// * `inc_tx` - The transform of the incident polygon.
 /// * `ref_idx` - The index of the reference face.
-/// * `inc_pos` - The position of the incident polygon.
 fn find_incident_face(
     v: &mut [Vector2<f64>; 2],
     ref_poly: &Polygon,
+    ref_tx: &Transform,
     inc_poly: &Polygon,
+    inc_tx: &Transform,
     ref_idx: usize,
-    inc_pos: Point2<f64>,
 ) {
-    let mut ref_norm = ref_poly.normals[ref_idx];
-
-    ref_norm = ref_poly.orient * ref_norm;
-    ref_norm = inc_poly.orient.transpose() * ref_norm;
+    // Bring the reference normal into the incident polygon's model space
+    let ref_norm = inc_tx.rotate_to_local(&ref_tx.rotate_to_world(&ref_poly.normals[ref_idx]));
 
     let mut inc_face_idx = 0;
-    let mut min_dot = INFINITY;
+    let mut min_dot = f64::INFINITY;
 
     for i in 0..inc_poly.vertices.len() {
         let dot = ref_norm.dot(&inc_poly.normals[i]);
@@ -299,13 +412,13 @@ fn find_incident_face(
         }
     }
 
-    v[0] = inc_poly.orient * inc_poly.vertices[inc_face_idx].coords + inc_pos.coords;
+    v[0] = inc_tx.to_world(&inc_poly.vertices[inc_face_idx]).coords;
     inc_face_idx = if inc_face_idx + 1 >= inc_poly.vertices.len() {
         0
     } else {
         inc_face_idx + 1
     };
-    v[1] = inc_poly.orient * inc_poly.vertices[inc_face_idx].coords + inc_pos.coords;
+    v[1] = inc_tx.to_world(&inc_poly.vertices[inc_face_idx]).coords;
 }
 
 /// Clips a line segment against a plane defined by a normal vector and a constant value.
@@ -353,11 +466,9 @@ fn clip(n: Vector2<f64>, c: f64, face: &mut [Vector2<f64>; 2]) -> usize {
 mod tests {
     use super::*;
 
-    use std::{cell::RefCell, rc::Rc};
+    use nalgebra::Point2;
 
-    use nalgebra::Matrix2;
-
-    use crate::{circle::Circle, object::Object, transform::Transform};
+    use crate::{circle::Circle, scene::Scene, shapes::Shapes};
 
     #[test]
     fn test_circle_circle_no_collision() {
@@ -369,11 +480,16 @@ mod tests {
         });
         let tx1 = Transform::new(Point2::new(0.0, 0.0));
         let tx2 = Transform::new(Point2::new(5.0, 0.0));
-        let a = RefCell::new(Object::new(circle1, tx1, None, None, None));
-        let b = RefCell::new(Object::new(circle2, tx2, None, None, None));
-        let mut manifold = Manifold::new(Rc::new(a), Rc::new(b));
-
-        circle_circle(&mut manifold);
+        let mut scene = Scene::new();
+        let a = scene.add_body(Object::builder(circle1).transform(tx1).build());
+        let b = scene.add_body(Object::builder(circle2).transform(tx2).build());
+        let mut manifold = Manifold::new(a, b);
+
+        circle_circle(
+            &mut manifold,
+            scene.body(a).unwrap(),
+            scene.body(b).unwrap(),
+        );
 
         assert_eq!(manifold.contact_count, 0);
     }
@@ -388,11 +504,16 @@ mod tests {
         });
         let tx1 = Transform::new(Point2::new(0.0, 0.0));
         let tx2 = Transform::new(Point2::new(3.0, 0.0));
-        let a = RefCell::new(Object::new(circle1, tx1, None, None, None));
-        let b = RefCell::new(Object::new(circle2, tx2, None, None, None));
-        let mut manifold = Manifold::new(Rc::new(a), Rc::new(b));
-
-        circle_circle(&mut manifold);
+        let mut scene = Scene::new();
+        let a = scene.add_body(Object::builder(circle1).transform(tx1).build());
+        let b = scene.add_body(Object::builder(circle2).transform(tx2).build());
+        let mut manifold = Manifold::new(a, b);
+
+        circle_circle(
+            &mut manifold,
+            scene.body(a).unwrap(),
+            scene.body(b).unwrap(),
+        );
 
         assert_eq!(manifold.contact_count, 1);
     }
@@ -415,17 +536,22 @@ mod tests {
                 Vector2::new(0.0, 1.0),
                 Vector2::new(-1.0, 0.0),
             ],
-            orient: Matrix2::identity(),
         });
 
         let tx_circle = Transform::new(Point2::new(10.0, 10.0));
         let tx_polygon = Transform::new(Point2::new(0.0, 0.0));
 
-        let a = RefCell::new(Object::new(circle, tx_circle, None, None, None));
-        let b = RefCell::new(Object::new(polygon, tx_polygon, None, None, None));
+        let mut scene = Scene::new();
+        let a = scene.add_body(Object::builder(circle).transform(tx_circle).build());
+        let b = scene.add_body(Object::builder(polygon).transform(tx_polygon).build());
 
-        let mut manifold = Manifold::new(Rc::new(a), Rc::new(b));
-        circle_polygon(&mut manifold, true);
+        let mut manifold = Manifold::new(a, b);
+        circle_polygon(
+            &mut manifold,
+            scene.body(a).unwrap(),
+            scene.body(b).unwrap(),
+            true,
+        );
 
         assert_eq!(manifold.contact_count, 0);
     }
@@ -448,17 +574,22 @@ mod tests {
                 Vector2::new(0.0, 1.0),
                 Vector2::new(-1.0, 0.0),
             ],
-            orient: Matrix2::identity(),
         });
 
         let tx_circle = Transform::new(Point2::new(0.0, 0.0));
         let tx_polygon = Transform::new(Point2::new(0.0, 0.0));
 
-        let a = RefCell::new(Object::new(circle, tx_circle, None, None, None));
-        let b = RefCell::new(Object::new(polygon, tx_polygon, None, None, None));
+        let mut scene = Scene::new();
+        let a = scene.add_body(Object::builder(circle).transform(tx_circle).build());
+        let b = scene.add_body(Object::builder(polygon).transform(tx_polygon).build());
 
-        let mut manifold = Manifold::new(Rc::new(a), Rc::new(b));
-        circle_polygon(&mut manifold, true);
+        let mut manifold = Manifold::new(a, b);
+        circle_polygon(
+            &mut manifold,
+            scene.body(a).unwrap(),
+            scene.body(b).unwrap(),
+            true,
+        );
 
         assert_eq!(manifold.contact_count, 1);
         assert!(manifold.penetration > OrderedFloat(0.0));
@@ -479,7 +610,6 @@ mod tests {
                 Vector2::new(0.0, 1.0),
                 Vector2::new(-1.0, 0.0),
             ],
-            orient: Matrix2::identity(),
         });
 
         let polygon2 = Shapes::Polygon(Polygon {
@@ -495,17 +625,21 @@ mod tests {
                 Vector2::new(0.0, 1.0),
                 Vector2::new(-1.0, 0.0),
             ],
-            orient: Matrix2::identity(),
         });
 
         let tx_polygon1 = Transform::new(Point2::new(0.0, 0.0));
         let tx_polygon2 = Transform::new(Point2::new(10.0, 10.0));
 
-        let a = RefCell::new(Object::new(polygon1, tx_polygon1, None, None, None));
-        let b = RefCell::new(Object::new(polygon2, tx_polygon2, None, None, None));
+        let mut scene = Scene::new();
+        let a = scene.add_body(Object::builder(polygon1).transform(tx_polygon1).build());
+        let b = scene.add_body(Object::builder(polygon2).transform(tx_polygon2).build());
 
-        let mut manifold = Manifold::new(Rc::new(a), Rc::new(b));
-        polygon_polygon(&mut manifold);
+        let mut manifold = Manifold::new(a, b);
+        polygon_polygon(
+            &mut manifold,
+            scene.body(a).unwrap(),
+            scene.body(b).unwrap(),
+        );
 
         assert_eq!(manifold.contact_count, 0);
     }
@@ -525,7 +659,6 @@ mod tests {
                 Vector2::new(0.0, 1.0),
                 Vector2::new(-1.0, 0.0),
             ],
-            orient: Matrix2::identity(),
         });
 
         let polygon2 = Shapes::Polygon(Polygon {
@@ -541,19 +674,304 @@ mod tests {
                 Vector2::new(0.0, 1.0),
                 Vector2::new(-1.0, 0.0),
             ],
-            orient: Matrix2::identity(),
         });
 
         let tx_polygon1 = Transform::new(Point2::new(0.0, 0.0));
         let tx_polygon2 = Transform::new(Point2::new(0.0, 0.0));
 
-        let a = RefCell::new(Object::new(polygon1, tx_polygon1, None, None, None));
-        let b = RefCell::new(Object::new(polygon2, tx_polygon2, None, None, None));
+        let mut scene = Scene::new();
+        let a = scene.add_body(Object::builder(polygon1).transform(tx_polygon1).build());
+        let b = scene.add_body(Object::builder(polygon2).transform(tx_polygon2).build());
 
-        let mut manifold = Manifold::new(Rc::new(a), Rc::new(b));
-        polygon_polygon(&mut manifold);
+        let mut manifold = Manifold::new(a, b);
+        polygon_polygon(
+            &mut manifold,
+            scene.body(a).unwrap(),
+            scene.body(b).unwrap(),
+        );
 
         assert!(manifold.contact_count > 0);
         assert!(manifold.penetration > OrderedFloat(0.0));
     }
+
+    #[test]
+    fn test_polygon_polygon_rotated_corner_collision() {
+        let floor = Shapes::Polygon(
+            Polygon::new(vec![
+                Point2::new(-10.0, -1.0),
+                Point2::new(10.0, -1.0),
+                Point2::new(10.0, 1.0),
+                Point2::new(-10.0, 1.0),
+            ])
+            .unwrap(),
+        );
+        let square = Shapes::Polygon(
+            Polygon::new(vec![
+                Point2::new(-1.0, -1.0),
+                Point2::new(1.0, -1.0),
+                Point2::new(1.0, 1.0),
+                Point2::new(-1.0, 1.0),
+            ])
+            .unwrap(),
+        );
+
+        // Standing on its corner, the lowest vertex sits 0.1 below the top of the floor
+        let half_diag = std::f64::consts::SQRT_2;
+        let tx_floor = Transform::new(Point2::new(0.0, 1.0));
+        let tx_square = Transform::with_orientation(
+            Point2::new(0.0, 0.1 - half_diag),
+            std::f64::consts::FRAC_PI_4,
+        );
+
+        let mut scene = Scene::new();
+        let a = scene.add_body(Object::builder(floor).transform(tx_floor).build());
+        let b = scene.add_body(Object::builder(square).transform(tx_square).build());
+
+        let mut manifold = Manifold::new(a, b);
+        polygon_polygon(
+            &mut manifold,
+            scene.body(a).unwrap(),
+            scene.body(b).unwrap(),
+        );
+
+        assert_eq!(manifold.contact_count, 1);
+        assert!((manifold.contacts[0] - Vector2::new(0.0, 0.1)).norm() < 1e-9);
+        assert!((manifold.normal - Vector2::new(0.0, -1.0)).norm() < 1e-9);
+        assert!((*manifold.penetration - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_polygon_polygon_rotation_separates() {
+        let floor = Shapes::Polygon(
+            Polygon::new(vec![
+                Point2::new(-10.0, -1.0),
+                Point2::new(10.0, -1.0),
+                Point2::new(10.0, 1.0),
+                Point2::new(-10.0, 1.0),
+            ])
+            .unwrap(),
+        );
+        let square = Shapes::Polygon(
+            Polygon::new(vec![
+                Point2::new(-1.0, -1.0),
+                Point2::new(1.0, -1.0),
+                Point2::new(1.0, 1.0),
+                Point2::new(-1.0, 1.0),
+            ])
+            .unwrap(),
+        );
+
+        // Flat, the square would overlap the floor; rotated onto its corner it clears it
+        let tx_floor = Transform::new(Point2::new(0.0, 1.0));
+        let tx_square =
+            Transform::with_orientation(Point2::new(0.0, -1.5), std::f64::consts::FRAC_PI_4);
+
+        let mut scene = Scene::new();
+        let a = scene.add_body(Object::builder(floor).transform(tx_floor).build());
+        let b = scene.add_body(Object::builder(square).transform(tx_square).build());
+
+        let mut manifold = Manifold::new(a, b);
+        polygon_polygon(
+            &mut manifold,
+            scene.body(a).unwrap(),
+            scene.body(b).unwrap(),
+        );
+
+        assert_eq!(manifold.contact_count, 0);
+    }
+
+    #[test]
+    fn test_circle_polygon_rotated_corner_collision() {
+        let circle = Shapes::Circle(Circle {
+            radius: OrderedFloat(1.0),
+        });
+        let square = Shapes::Polygon(
+            Polygon::new(vec![
+                Point2::new(-1.0, -1.0),
+                Point2::new(1.0, -1.0),
+                Point2::new(1.0, 1.0),
+                Point2::new(-1.0, 1.0),
+            ])
+            .unwrap(),
+        );
+
+        let half_diag = std::f64::consts::SQRT_2;
+        let tx_circle = Transform::new(Point2::new(half_diag + 0.9, 0.0));
+        let tx_square =
+            Transform::with_orientation(Point2::new(0.0, 0.0), std::f64::consts::FRAC_PI_4);
+
+        let mut scene = Scene::new();
+        let a = scene.add_body(Object::builder(circle).transform(tx_circle).build());
+        let b = scene.add_body(Object::builder(square).transform(tx_square).build());
+
+        let mut manifold = Manifold::new(a, b);
+        circle_polygon(
+            &mut manifold,
+            scene.body(a).unwrap(),
+            scene.body(b).unwrap(),
+            true,
+        );
+
+        assert_eq!(manifold.contact_count, 1);
+        assert!((manifold.contacts[0] - Vector2::new(half_diag, 0.0)).norm() < 1e-9);
+        assert!((manifold.normal - Vector2::new(-1.0, 0.0)).norm() < 1e-9);
+    }
+
+    /// Collides two shapes with both a hand-written routine and the generic GJK/EPA path, checking
+    /// that they agree on whether and along which normal the shapes touch.
+    fn cross_check<F>(
+        shape_a: Shapes,
+        tx_a: Transform,
+        shape_b: Shapes,
+        tx_b: Transform,
+        fast: F,
+    ) -> (Manifold, Manifold)
+    where
+        F: Fn(&mut Manifold, &Object, &Object),
+    {
+        let mut scene = Scene::new();
+        let a = scene.add_body(Object::builder(shape_a).transform(tx_a).build());
+        let b = scene.add_body(Object::builder(shape_b).transform(tx_b).build());
+        let (body_a, body_b) = (scene.body(a).unwrap(), scene.body(b).unwrap());
+
+        let mut expected = Manifold::new(a, b);
+        fast(&mut expected, body_a, body_b);
+        let mut generic = Manifold::new(a, b);
+        convex_convex(&mut generic, body_a, body_b);
+
+        assert_eq!(generic.contact_count, expected.contact_count);
+        if expected.contact_count > 0 {
+            assert!((generic.normal - expected.normal).norm() < 1e-5);
+        }
+
+        (expected, generic)
+    }
+
+    fn square(half: f64) -> Shapes {
+        Shapes::Polygon(
+            Polygon::new(vec![
+                Point2::new(-half, -half),
+                Point2::new(half, -half),
+                Point2::new(half, half),
+                Point2::new(-half, half),
+            ])
+            .unwrap(),
+        )
+    }
+
+    fn circle(radius: f64) -> Shapes {
+        Shapes::Circle(Circle::new(radius).unwrap())
+    }
+
+    #[test]
+    fn test_convex_convex_matches_circle_circle() {
+        let tx_a = Transform::new(Point2::new(1.0, 1.0));
+
+        let (expected, generic) = cross_check(
+            circle(1.0),
+            tx_a,
+            circle(0.5),
+            Transform::new(Point2::new(1.6, 1.8)),
+            circle_circle,
+        );
+        assert!((*generic.penetration - *expected.penetration).abs() < 1e-6);
+
+        cross_check(
+            circle(1.0),
+            tx_a,
+            circle(0.5),
+            Transform::new(Point2::new(3.0, 1.0)),
+            circle_circle,
+        );
+    }
+
+    #[test]
+    fn test_convex_convex_matches_circle_polygon() {
+        let tx_square = Transform::with_orientation(Point2::new(2.0, 0.0), 0.3);
+
+        // Against a face, where both place the contact at the circle's deepest point
+        let face = tx_square.to_world(&Point2::new(0.0, -1.4));
+        let (expected, generic) = cross_check(
+            circle(0.5),
+            Transform::new(face),
+            square(1.0),
+            tx_square,
+            |m, a, b| circle_polygon(m, a, b, true),
+        );
+        assert!((*generic.penetration - *expected.penetration).abs() < 1e-6);
+        assert!((generic.contacts[0] - expected.contacts[0]).norm() < 1e-5);
+
+        let (expected, generic) = cross_check(
+            square(1.0),
+            tx_square,
+            circle(0.5),
+            Transform::new(face),
+            |m, a, b| circle_polygon(m, a, b, false),
+        );
+        assert!((*generic.penetration - *expected.penetration).abs() < 1e-6);
+        assert!((generic.contacts[0] - expected.contacts[0]).norm() < 1e-5);
+
+        // Against a corner, where `circle_polygon` measures the depth from the face rather than
+        // the vertex, so only the generic path finds the true depth
+        let corner = tx_square.to_world(&Point2::new(1.2, 1.3));
+        let depth = 0.5 - 0.13f64.sqrt();
+        let (_, generic) = cross_check(
+            circle(0.5),
+            Transform::new(corner),
+            square(1.0),
+            tx_square,
+            |m, a, b| circle_polygon(m, a, b, true),
+        );
+        assert!((*generic.penetration - depth).abs() < 1e-6);
+        cross_check(
+            square(1.0),
+            tx_square,
+            circle(0.5),
+            Transform::new(corner),
+            |m, a, b| circle_polygon(m, a, b, false),
+        );
+    }
+
+    #[test]
+    fn test_convex_convex_matches_polygon_polygon() {
+        // Resting face to face, offset sideways so that clipping trims the incident edge
+        let (expected, generic) = cross_check(
+            square(2.0),
+            Transform::default(),
+            square(1.0),
+            Transform::new(Point2::new(2.5, -2.9)),
+            polygon_polygon,
+        );
+        assert_eq!(generic.contact_count, 2);
+        assert!((*generic.penetration - *expected.penetration).abs() < 1e-9);
+        // Either face may be chosen as the reference, which moves the contacts across the overlap
+        // but not along the faces
+        for c in &generic.contacts {
+            assert!(expected.contacts.iter().any(|e| (c.x - e.x).abs() < 1e-9));
+        }
+
+        // Standing on a corner
+        let (expected, generic) = cross_check(
+            square(2.0),
+            Transform::default(),
+            square(1.0),
+            Transform::with_orientation(
+                Point2::new(0.5, -2.0 - std::f64::consts::SQRT_2 + 0.1),
+                std::f64::consts::FRAC_PI_4,
+            ),
+            polygon_polygon,
+        );
+        assert_eq!(generic.contact_count, 1);
+        assert!((*generic.penetration - *expected.penetration).abs() < 1e-9);
+        assert!((generic.contacts[0] - expected.contacts[0]).norm() < 1e-9);
+
+        // Apart
+        cross_check(
+            square(2.0),
+            Transform::default(),
+            square(1.0),
+            Transform::new(Point2::new(0.0, -3.5)),
+            polygon_polygon,
+        );
+    }
 }
@@ -0,0 +1,100 @@
+/// Decides which fixtures may collide with each other, using the same rules as Box2D.
+///
+/// Every fixture belongs to one or more categories and has a mask of the categories it collides
+/// with; two fixtures collide only if each one's mask accepts the other's category. A non-zero
+/// group index overrides this for fixtures in the same group: they always collide if it is
+/// positive, and never collide if it is negative.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CollisionFilter {
+    /// The categories the fixture belongs to, usually a single bit.
+    pub category_bits: u16,
+    /// The categories the fixture collides with.
+    pub mask_bits: u16,
+    /// The group of the fixture, or zero for none.
+    pub group_index: i16,
+}
+
+impl Default for CollisionFilter {
+    /// Creates a filter in the first category that collides with everything.
+    fn default() -> Self {
+        CollisionFilter {
+            category_bits: 0x0001,
+            mask_bits: 0xFFFF,
+            group_index: 0,
+        }
+    }
+}
+
+impl CollisionFilter {
+    /// Creates a new `CollisionFilter` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `category_bits` - The categories the fixture belongs to.
+    /// * `mask_bits` - The categories the fixture collides with.
+    /// * `group_index` - The group of the fixture, or zero for none.
+    ///
+    /// # Returns
+    ///
+    /// A new `CollisionFilter` instance.
+    pub fn new(category_bits: u16, mask_bits: u16, group_index: i16) -> Self {
+        CollisionFilter {
+            category_bits,
+            mask_bits,
+            group_index,
+        }
+    }
+
+    /// Checks whether fixtures with these two filters should collide.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The filter of the other fixture.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the fixtures should collide. The result does not depend on the order.
+    pub fn should_collide(&self, other: &CollisionFilter) -> bool {
+        if self.group_index == other.group_index && self.group_index != 0 {
+            return self.group_index > 0;
+        }
+
+        (self.mask_bits & other.category_bits) != 0 && (other.mask_bits & self.category_bits) != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAYER: u16 = 0x0002;
+    const DEBRIS: u16 = 0x0004;
+
+    #[test]
+    fn test_categories_and_masks() {
+        let ground = CollisionFilter::default();
+        let debris = CollisionFilter::new(DEBRIS, !DEBRIS, 0);
+        let player = CollisionFilter::new(PLAYER, 0xFFFF, 0);
+
+        assert!(ground.should_collide(&debris));
+        assert!(player.should_collide(&debris));
+        assert!(!debris.should_collide(&debris));
+
+        // Both masks have to accept the other's category
+        let ghost = CollisionFilter::new(PLAYER, DEBRIS, 0);
+        assert!(!ghost.should_collide(&ground));
+        assert!(!ground.should_collide(&ghost));
+        assert!(ghost.should_collide(&debris));
+    }
+
+    #[test]
+    fn test_groups_override_masks() {
+        let never = CollisionFilter::new(PLAYER, 0xFFFF, -1);
+        assert!(!never.should_collide(&never));
+        assert!(never.should_collide(&CollisionFilter::default()));
+
+        let always = CollisionFilter::new(DEBRIS, 0, 3);
+        assert!(always.should_collide(&always));
+        assert!(!always.should_collide(&CollisionFilter::new(DEBRIS, 0, 4)));
+    }
+}
@@ -0,0 +1,432 @@
+#[cfg(feature = "render-piston")]
+use graphics::Context;
+#[cfg(feature = "render-piston")]
+use opengl_graphics::GlGraphics;
+
+use nalgebra::{Point2, Vector2};
+
+use crate::aabb::Aabb;
+use crate::constants::GEOMETRY_TOLERANCE;
+use crate::custom_math::cross_v_v;
+use crate::mass_data::MassData;
+use crate::polygon::{area_moments, signed_area, validate, Polygon};
+use crate::ray::Ray;
+use crate::shape_error::ShapeError;
+use crate::shapes::{Shape, ShapeDiscriminant};
+use crate::transform::Transform;
+use crate::types::KilogramPerCubicMeter;
+
+/// Represents a concave polygon in the simulation.
+///
+/// The narrow phase only handles convex polygons, so the outline is split into convex pieces
+/// which all share the owning object's model space.
+pub struct ConcavePolygon {
+    /// The convex pieces the outline was split into.
+    pub pieces: Vec<Polygon>,
+}
+
+impl ConcavePolygon {
+    /// Creates a new concave polygon by splitting a simple outline into convex pieces.
+    ///
+    /// The outline is triangulated by ear clipping, after which neighbouring pieces are merged
+    /// for as long as the result stays convex.
+    ///
+    /// # Arguments
+    ///
+    /// * `outline` - The vertices of the outline, in either winding order.
+    ///
+    /// # Returns
+    ///
+    /// A new `ConcavePolygon` instance, or a `ShapeError` describing why the vertices do not form a
+    /// simple polygon.
+    pub fn new(mut outline: Vec<Point2<f64>>) -> Result<Self, ShapeError> {
+        validate(&outline)?;
+
+        if signed_area(&outline) < 0.0 {
+            outline.reverse();
+        }
+
+        let pieces = merge_convex(triangulate(&outline))
+            .into_iter()
+            .map(|piece| Polygon::from_convex(remove_collinear(piece)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { pieces })
+    }
+}
+
+impl Shape for ConcavePolygon {
+    /// Calculates the mass and moment of inertia data for the concave polygon.
+    ///
+    /// The pieces are summed up and then moved so that the centroid of the whole outline is at
+    /// the origin.
+    ///
+    /// # Arguments
+    ///
+    /// * `density` - The density of the material the polygon is made of.
+    ///
+    /// # Returns
+    ///
+    /// A `MassData` structure containing the calculated mass and moment of inertia.
+    fn calculate_mass_data(&mut self, density: KilogramPerCubicMeter) -> MassData {
+        let mut area = 0.0;
+        let mut centroid = Vector2::zeros();
+        let mut second_moment = 0.0;
+
+        for piece in &self.pieces {
+            let (piece_area, piece_centroid, piece_second_moment) = area_moments(&piece.vertices);
+
+            area += piece_area;
+            centroid += piece_area * piece_centroid;
+            second_moment += piece_second_moment;
+        }
+
+        centroid *= 1.0 / area;
+
+        for piece in &mut self.pieces {
+            for vert in &mut piece.vertices {
+                vert.coords -= centroid;
+            }
+        }
+
+        let mmi = second_moment - area * centroid.norm_squared();
+
+        MassData::new(density * area, mmi * density)
+    }
+
+    /// Returns the centroid of the whole outline in model space.
+    fn centroid(&self) -> Point2<f64> {
+        let mut area = 0.0;
+        let mut centroid = Vector2::zeros();
+
+        for piece in &self.pieces {
+            let (piece_area, piece_centroid, _) = area_moments(&piece.vertices);
+
+            area += piece_area;
+            centroid += piece_area * piece_centroid;
+        }
+
+        Point2::from(centroid / area)
+    }
+
+    /// Calculates the world space bounding box of the concave polygon.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transform that brings the polygon into world space.
+    fn bounding_box(&self, tx: &Transform) -> Aabb {
+        self.pieces
+            .iter()
+            .map(|piece| piece.bounding_box(tx))
+            .reduce(|a, b| a.union(&b))
+            .expect("a concave polygon has at least one piece")
+    }
+
+    /// Finds where a ray first enters the concave polygon.
+    ///
+    /// # Arguments
+    ///
+    /// * `ray` - The ray, in world space.
+    /// * `tx` - The transform that brings the polygon into world space.
+    ///
+    /// # Returns
+    ///
+    /// The distance along the ray and the world space normal of the edge that was hit, or `None`
+    /// if the ray misses within its maximum distance or starts inside the outline.
+    fn ray_cast(&self, ray: &Ray, tx: &Transform) -> Option<(f64, Vector2<f64>)> {
+        // Starting inside one piece would otherwise hit the edge it shares with the next
+        if self
+            .pieces
+            .iter()
+            .any(|piece| piece.contains_point(&ray.origin, tx))
+        {
+            return None;
+        }
+
+        self.pieces
+            .iter()
+            .filter_map(|piece| piece.ray_cast(ray, tx))
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+    }
+
+    /// Draws every piece of the concave polygon.
+    ///
+    /// # Arguments
+    ///
+    /// * `c` - The graphics context.
+    /// * `gl` - The OpenGL graphics.
+    /// * `tx` - The transform information.
+    #[cfg(feature = "render-piston")]
+    fn draw(&self, c: Context, gl: &mut GlGraphics, tx: &Transform) {
+        for piece in &self.pieces {
+            piece.draw(c, gl, tx);
+        }
+    }
+
+    /// Returns the discriminant associated with the concave polygon shape.
+    fn discriminant(&self) -> ShapeDiscriminant {
+        ShapeDiscriminant::ConcavePolygon
+    }
+}
+
+/// Returns the cross product of the two edges meeting at a corner, scaled by their lengths.
+///
+/// # Returns
+///
+/// The sine of the turn at `b`: positive for a left turn, negative for a right turn.
+fn corner_sine(a: Point2<f64>, b: Point2<f64>, c: Point2<f64>) -> f64 {
+    let e1 = b - a;
+    let e2 = c - b;
+
+    cross_v_v(&e1, &e2) / (e1.norm() * e2.norm())
+}
+
+/// Checks whether a point lies inside or on the border of a counter-clockwise triangle.
+fn in_triangle(p: Point2<f64>, a: Point2<f64>, b: Point2<f64>, c: Point2<f64>) -> bool {
+    cross_v_v(&(b - a), &(p - a)) >= 0.0
+        && cross_v_v(&(c - b), &(p - b)) >= 0.0
+        && cross_v_v(&(a - c), &(p - c)) >= 0.0
+}
+
+/// Splits a simple polygon into triangles by ear clipping.
+///
+/// # Arguments
+///
+/// * `verts` - The vertices of the polygon, in counter-clockwise order.
+///
+/// # Returns
+///
+/// The triangles, each in counter-clockwise order.
+fn triangulate(verts: &[Point2<f64>]) -> Vec<Vec<Point2<f64>>> {
+    let mut remaining: Vec<usize> = (0..verts.len()).collect();
+    let mut triangles = Vec::with_capacity(verts.len() - 2);
+
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let corner = |i: usize| {
+            (
+                verts[remaining[(i + n - 1) % n]],
+                verts[remaining[i]],
+                verts[remaining[(i + 1) % n]],
+            )
+        };
+        let is_ear = |i: usize| {
+            let (a, b, c) = corner(i);
+
+            corner_sine(a, b, c) > GEOMETRY_TOLERANCE
+                && (0..n)
+                    .filter(|&j| j != i && j != (i + n - 1) % n && j != (i + 1) % n)
+                    .all(|j| !in_triangle(verts[remaining[j]], a, b, c))
+        };
+
+        // A simple polygon always has an ear, but rounding can hide it, in which case the most
+        // convex corner is clipped instead
+        let ear = (0..n).find(|&i| is_ear(i)).unwrap_or_else(|| {
+            (0..n)
+                .max_by(|&i, &j| {
+                    let (a1, b1, c1) = corner(i);
+                    let (a2, b2, c2) = corner(j);
+                    corner_sine(a1, b1, c1).total_cmp(&corner_sine(a2, b2, c2))
+                })
+                .unwrap_or(0)
+        });
+
+        let (a, b, c) = corner(ear);
+        triangles.push(vec![a, b, c]);
+        remaining.remove(ear);
+    }
+
+    triangles.push(remaining.iter().map(|&i| verts[i]).collect());
+
+    triangles
+}
+
+/// Merges pieces across their shared diagonals for as long as the merged piece stays convex.
+///
+/// # Arguments
+///
+/// * `pieces` - Convex pieces in counter-clockwise order, sharing exact vertex positions.
+///
+/// # Returns
+///
+/// The merged pieces.
+fn merge_convex(mut pieces: Vec<Vec<Point2<f64>>>) -> Vec<Vec<Point2<f64>>> {
+    'search: loop {
+        for i in 0..pieces.len() {
+            for j in i + 1..pieces.len() {
+                if let Some(merged) = try_merge(&pieces[i], &pieces[j]) {
+                    pieces[i] = merged;
+                    pieces.swap_remove(j);
+                    continue 'search;
+                }
+            }
+        }
+
+        return pieces;
+    }
+}
+
+/// Merges two pieces if they share an edge and the result is convex.
+///
+/// # Arguments
+///
+/// * `p` - The first piece, in counter-clockwise order.
+/// * `q` - The second piece, in counter-clockwise order.
+///
+/// # Returns
+///
+/// The merged piece in counter-clockwise order, or `None` if the pieces cannot be merged.
+fn try_merge(p: &[Point2<f64>], q: &[Point2<f64>]) -> Option<Vec<Point2<f64>>> {
+    let (np, nq) = (p.len(), q.len());
+
+    for k in 0..np {
+        let (a, b) = (p[k], p[(k + 1) % np]);
+
+        // Both pieces are counter-clockwise, so q walks the shared edge the other way round
+        if let Some(m) = (0..nq).find(|&m| q[m] == b && q[(m + 1) % nq] == a) {
+            // Walk p from b round to a, then q from after a round to before b
+            let mut merged: Vec<Point2<f64>> = (1..=np).map(|o| p[(k + o) % np]).collect();
+            merged.extend((2..nq).map(|o| q[(m + o) % nq]));
+
+            let n = merged.len();
+            let convex = (0..n).all(|i| {
+                corner_sine(merged[(i + n - 1) % n], merged[i], merged[(i + 1) % n])
+                    >= -GEOMETRY_TOLERANCE
+            });
+
+            return convex.then_some(merged);
+        }
+    }
+
+    None
+}
+
+/// Removes vertices that lie on the line through their neighbours.
+///
+/// Merging two triangles along a diagonal can leave a vertex in the middle of a straight edge,
+/// which `Polygon::from_convex` rejects.
+fn remove_collinear(mut verts: Vec<Point2<f64>>) -> Vec<Point2<f64>> {
+    let mut i = 0;
+
+    while i < verts.len() && verts.len() > 3 {
+        let n = verts.len();
+
+        if corner_sine(verts[(i + n - 1) % n], verts[i], verts[(i + 1) % n]).abs()
+            <= GEOMETRY_TOLERANCE
+        {
+            verts.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+
+    verts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(coords: &[(f64, f64)]) -> Vec<Point2<f64>> {
+        coords.iter().map(|&(x, y)| Point2::new(x, y)).collect()
+    }
+
+    /// Checks that every piece is convex and that the pieces add up to the outline's mass data.
+    fn assert_matches_outline(outline: &[Point2<f64>]) -> ConcavePolygon {
+        let mut concave = ConcavePolygon::new(outline.to_vec()).unwrap();
+
+        for piece in &concave.pieces {
+            let n = piece.vertices.len();
+            for i in 0..n {
+                let turn = corner_sine(
+                    piece.vertices[(i + n - 1) % n],
+                    piece.vertices[i],
+                    piece.vertices[(i + 1) % n],
+                );
+                assert!(turn > 0.0);
+            }
+        }
+
+        let mut ccw = outline.to_vec();
+        if signed_area(&ccw) < 0.0 {
+            ccw.reverse();
+        }
+        let (area, centroid, second_moment) = area_moments(&ccw);
+        let expected_mmi = second_moment - area * centroid.norm_squared();
+
+        let mass_data = concave.calculate_mass_data(2.0);
+
+        assert!((mass_data.mass - 2.0 * area).abs() < 1e-9);
+        assert!((1.0 / mass_data.inv_m_inertia - 2.0 * expected_mmi).abs() < 1e-6);
+
+        concave
+    }
+
+    #[test]
+    fn test_l_shape() {
+        let outline = points(&[
+            (0.0, 0.0),
+            (3.0, 0.0),
+            (3.0, 1.0),
+            (1.0, 1.0),
+            (1.0, 3.0),
+            (0.0, 3.0),
+        ]);
+
+        let concave = assert_matches_outline(&outline);
+
+        assert_eq!(concave.pieces.len(), 2);
+    }
+
+    #[test]
+    fn test_u_shape() {
+        // Given clockwise, to check the winding is fixed up
+        let outline = points(&[
+            (0.0, 0.0),
+            (0.0, 3.0),
+            (1.0, 3.0),
+            (1.0, 1.0),
+            (2.0, 1.0),
+            (2.0, 3.0),
+            (3.0, 3.0),
+            (3.0, 0.0),
+        ]);
+
+        let concave = assert_matches_outline(&outline);
+
+        assert_eq!(concave.pieces.len(), 3);
+    }
+
+    #[test]
+    fn test_star() {
+        let outline: Vec<Point2<f64>> = (0..10)
+            .map(|i| {
+                let angle = i as f64 * std::f64::consts::PI / 5.0;
+                let radius = if i % 2 == 0 { 2.0 } else { 0.8 };
+                Point2::new(radius * angle.cos(), radius * angle.sin())
+            })
+            .collect();
+
+        let concave = assert_matches_outline(&outline);
+
+        // One piece per point at the very least, as no two points can share a convex piece
+        assert!(concave.pieces.len() >= 5);
+    }
+
+    #[test]
+    fn test_convex_outline_is_one_piece() {
+        let outline = points(&[(0.0, 0.0), (2.0, 0.0), (3.0, 1.0), (1.0, 2.0), (-1.0, 1.0)]);
+
+        let concave = assert_matches_outline(&outline);
+
+        assert_eq!(concave.pieces.len(), 1);
+        assert_eq!(concave.pieces[0].vertices.len(), 5);
+    }
+
+    #[test]
+    fn test_invalid_outline() {
+        let res = ConcavePolygon::new(points(&[(0.0, 0.0), (3.0, 2.0), (3.0, 0.0), (0.0, 3.0)]));
+
+        assert!(matches!(res, Err(ShapeError::SelfIntersecting { .. })));
+    }
+}
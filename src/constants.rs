@@ -9,6 +9,33 @@ pub const GREEN: [f32; 4] = [0.0, 1.0, 0.0, 1.0];
 pub const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
 pub const BLACK: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
 pub const ONE_THIRD: f64 = 1.0 / 3.0;
+// Distances, areas and sines below this are treated as zero when validating shapes
+pub const GEOMETRY_TOLERANCE: f64 = 1e-9;
+pub const VELOCITY_ITERATIONS: usize = 10;
+// How far the broad phase grows bounding boxes so that small moves need no update
+pub const AABB_MARGIN: f64 = 0.1;
+// Bodies covering more spatial hash grid cells than this are tested against every other body
+// instead of being bucketed
+pub const MAX_GRID_CELLS_PER_BODY: usize = 64;
+// How far short of contact shape casts stop, so that the result touches without overlapping
+pub const LINEAR_SLOP: f64 = 0.005;
+// The furthest joints move bodies back into place in one step, so that large errors resolve
+// smoothly
+pub const MAX_LINEAR_CORRECTION: f64 = 0.2;
+// GJK normally converges in a handful of iterations; this only guards against cycling
+pub const GJK_MAX_ITERATIONS: usize = 20;
+// GJK stops once a new support point brings the simplex less than this fraction of the squared
+// distance closer to the origin, which is how it converges on curved shapes
+pub const GJK_TOLERANCE: f64 = 1e-10;
+// EPA stops once a new support point reaches no further than this beyond the closest edge
+pub const EPA_TOLERANCE: f64 = 1e-12;
+// Curved shapes make EPA refine forever, so it is cut off after this many points
+pub const EPA_MAX_ITERATIONS: usize = 64;
+// How far either side of a direction to look for the two ends of a supporting edge, in radians
+pub const SUPPORT_EDGE_ANGLE: f64 = 1e-4;
+// How much more tilted, as the sine of its angle to the contact face, the edge of the first shape
+// may be and still be picked as the reference face, so that near ties keep a stable choice
+pub const REFERENCE_FACE_TOLERANCE: f64 = 1e-4;
 // For positional correction
 pub const PEN_ALLOWANCE: NormalizedCoefficient = OrderedFloat(0.05);
 pub const PERCENT_CORRECTION: NormalizedCoefficient = OrderedFloat(0.4);
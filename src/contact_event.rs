@@ -0,0 +1,57 @@
+use nalgebra::Vector2;
+
+use crate::fixture::PartId;
+use crate::manifold::Manifold;
+use crate::scene::BodyHandle;
+
+/// A snapshot of a contact between two convex parts at the end of a step.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContactInfo {
+    /// The first body.
+    pub a: BodyHandle,
+    /// The part of the first body that is touching.
+    pub part_a: PartId,
+    /// The second body.
+    pub b: BodyHandle,
+    /// The part of the second body that is touching.
+    pub part_b: PartId,
+    /// The contact normal, pointing from `a` to `b`.
+    pub normal: Vector2<f64>,
+    /// The contact points in world coordinates, of which only the first `contact_count` are used.
+    pub contacts: [Vector2<f64>; 2],
+    /// The number of contact points.
+    pub contact_count: usize,
+    /// The total impulse the solver applied along the normal at each contact point.
+    pub normal_impulses: [f64; 2],
+    /// The total friction impulse the solver applied at each contact point, along the normal
+    /// turned a quarter clockwise.
+    pub tangent_impulses: [f64; 2],
+}
+
+impl From<&Manifold> for ContactInfo {
+    fn from(m: &Manifold) -> Self {
+        ContactInfo {
+            a: m.a,
+            part_a: m.part_a,
+            b: m.b,
+            part_b: m.part_b,
+            normal: m.normal,
+            contacts: m.contacts,
+            contact_count: m.contact_count,
+            normal_impulses: m.normal_impulses,
+            tangent_impulses: m.tangent_impulses,
+        }
+    }
+}
+
+/// A change in which parts touch, recorded by `Scene::step`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ContactEvent {
+    /// The parts started touching during the step.
+    Begin(ContactInfo),
+    /// The parts were already touching in the previous step and still are.
+    Persist(ContactInfo),
+    /// The parts stopped touching during the step, including because one of the bodies was
+    /// removed from the scene. The info is the one last reported for the contact.
+    End(ContactInfo),
+}
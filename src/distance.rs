@@ -0,0 +1,232 @@
+use nalgebra::{Point2, Vector2};
+
+use crate::gjk::{closest_core_points, Proxy};
+use crate::shapes::Shapes;
+use crate::transform::Transform;
+
+/// The separation between two shapes and the points where they come closest.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Distance {
+    /// The gap between the shapes, or zero if they touch or overlap.
+    pub distance: f64,
+    /// The point on the first shape closest to the second, in world space.
+    pub point_a: Point2<f64>,
+    /// The point on the second shape closest to the first, in world space.
+    pub point_b: Point2<f64>,
+    /// The unit direction from `point_a` to `point_b`.
+    ///
+    /// This is zero when the shapes overlap too deeply for a direction to be found.
+    pub normal: Vector2<f64>,
+}
+
+// Adapted from Box2D's b2Distance: https://github.com/erincatto/box2d
+/// Finds the distance and closest points between two shapes using GJK.
+///
+/// Concave shapes are measured piece by piece, keeping the closest pair of pieces. When the
+/// shapes overlap, the distance is zero and both witness points lie at the same spot inside the
+/// overlap.
+///
+/// # Arguments
+///
+/// * `shape_a` - The first shape.
+/// * `tx_a` - The transform that brings the first shape into world space.
+/// * `shape_b` - The second shape.
+/// * `tx_b` - The transform that brings the second shape into world space.
+///
+/// # Returns
+///
+/// The distance, witness points and normal.
+pub fn distance(
+    shape_a: &Shapes,
+    tx_a: &Transform,
+    shape_b: &Shapes,
+    tx_b: &Transform,
+) -> Distance {
+    let mut closest: Option<Distance> = None;
+
+    for i in 0..shape_a.part_count() {
+        for j in 0..shape_b.part_count() {
+            let a = Proxy {
+                part: shape_a.part(i),
+                tx: *tx_a,
+            };
+            let b = Proxy {
+                part: shape_b.part(j),
+                tx: *tx_b,
+            };
+            let d = part_distance(&a, &b);
+
+            if closest.is_none_or(|c| d.distance < c.distance) {
+                closest = Some(d);
+            }
+        }
+    }
+
+    closest.expect("shapes have at least one part")
+}
+
+/// Finds the distance and closest points between two convex parts.
+fn part_distance(a: &Proxy, b: &Proxy) -> Distance {
+    let (core_a, core_b) = closest_core_points(a, b);
+
+    let gap = core_b - core_a;
+    let core_distance = gap.norm();
+    let normal = gap
+        .try_normalize(f64::EPSILON)
+        .unwrap_or_else(Vector2::zeros);
+    let radii = a.radius() + b.radius();
+
+    if core_distance > radii {
+        return Distance {
+            distance: core_distance - radii,
+            point_a: a.surface_point(&core_a, &normal),
+            point_b: b.surface_point(&core_b, &-normal),
+            normal,
+        };
+    }
+
+    // Touching or overlapping, so meet halfway between the two surfaces
+    let mid = core_a + normal * (a.radius() - 0.5 * (radii - core_distance));
+
+    Distance {
+        distance: 0.0,
+        point_a: mid,
+        point_b: mid,
+        normal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::f64::consts::FRAC_PI_4;
+
+    use crate::circle::Circle;
+    use crate::concave_polygon::ConcavePolygon;
+    use crate::polygon::Polygon;
+
+    fn circle(radius: f64) -> Shapes {
+        Shapes::Circle(Circle::new(radius).unwrap())
+    }
+
+    fn unit_square() -> Shapes {
+        Shapes::Polygon(
+            Polygon::new(vec![
+                Point2::new(-0.5, -0.5),
+                Point2::new(0.5, -0.5),
+                Point2::new(0.5, 0.5),
+                Point2::new(-0.5, 0.5),
+            ])
+            .unwrap(),
+        )
+    }
+
+    fn assert_close(a: Point2<f64>, b: Point2<f64>) {
+        assert!((a - b).norm() < 1e-9, "{a} != {b}");
+    }
+
+    #[test]
+    fn test_circle_circle() {
+        let d = distance(
+            &circle(1.0),
+            &Transform::default(),
+            &circle(0.5),
+            &Transform::new(Point2::new(4.0, 3.0)),
+        );
+
+        assert!((d.distance - 3.5).abs() < 1e-9);
+        assert_close(d.point_a, Point2::new(0.8, 0.6));
+        assert_close(d.point_b, Point2::new(3.6, 2.7));
+        assert!((d.normal - Vector2::new(0.8, 0.6)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_rotated_polygon_circle() {
+        // Standing on a corner, which points at the circle
+        let tx_a = Transform::with_orientation(Point2::origin(), FRAC_PI_4);
+        let tx_b = Transform::new(Point2::new(3.0, 0.0));
+        let corner = 0.5f64.sqrt();
+
+        let d = distance(&unit_square(), &tx_a, &circle(0.5), &tx_b);
+        assert!((d.distance - (2.5 - corner)).abs() < 1e-9);
+        assert_close(d.point_a, Point2::new(corner, 0.0));
+        assert_close(d.point_b, Point2::new(2.5, 0.0));
+
+        // Measuring the other way round swaps the points and flips the normal
+        let e = distance(&circle(0.5), &tx_b, &unit_square(), &tx_a);
+        assert!((e.distance - d.distance).abs() < 1e-9);
+        assert_close(e.point_a, d.point_b);
+        assert_close(e.point_b, d.point_a);
+        assert!((e.normal + d.normal).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_polygon_polygon_faces() {
+        let d = distance(
+            &unit_square(),
+            &Transform::default(),
+            &unit_square(),
+            &Transform::new(Point2::new(3.0, 0.2)),
+        );
+
+        // Any pair of points across the facing edges is closest
+        assert!((d.distance - 2.0).abs() < 1e-9);
+        assert!((d.point_a.x - 0.5).abs() < 1e-9);
+        assert!((d.point_b - d.point_a - Vector2::new(2.0, 0.0)).norm() < 1e-9);
+        assert!((d.normal - Vector2::new(1.0, 0.0)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_overlapping_shapes() {
+        // Only the circle's rounded part overlaps the square
+        let d = distance(
+            &unit_square(),
+            &Transform::default(),
+            &circle(0.5),
+            &Transform::new(Point2::new(0.8, 0.0)),
+        );
+        assert_eq!(d.distance, 0.0);
+        assert_close(d.point_a, Point2::new(0.4, 0.0));
+        assert_eq!(d.point_a, d.point_b);
+
+        // The square's core overlaps the circle's center
+        let d = distance(
+            &unit_square(),
+            &Transform::default(),
+            &circle(0.5),
+            &Transform::new(Point2::new(0.2, 0.1)),
+        );
+        assert_eq!(d.distance, 0.0);
+        assert_eq!(d.point_a, d.point_b);
+    }
+
+    #[test]
+    fn test_concave_shape_uses_closest_piece() {
+        // A U shape with a ball sitting in its notch, closer to the right arm
+        let cup = Shapes::ConcavePolygon(
+            ConcavePolygon::new(vec![
+                Point2::new(-2.0, 0.0),
+                Point2::new(2.0, 0.0),
+                Point2::new(2.0, 3.0),
+                Point2::new(1.0, 3.0),
+                Point2::new(1.0, 1.0),
+                Point2::new(-1.0, 1.0),
+                Point2::new(-1.0, 3.0),
+                Point2::new(-2.0, 3.0),
+            ])
+            .unwrap(),
+        );
+
+        let d = distance(
+            &cup,
+            &Transform::default(),
+            &circle(0.25),
+            &Transform::new(Point2::new(0.5, 2.5)),
+        );
+        assert!((d.distance - 0.25).abs() < 1e-9);
+        assert_close(d.point_a, Point2::new(1.0, 2.5));
+        assert_close(d.point_b, Point2::new(0.75, 2.5));
+        assert!((d.normal - Vector2::new(-1.0, 0.0)).norm() < 1e-9);
+    }
+}
@@ -0,0 +1,538 @@
+use nalgebra::{Point2, Vector2};
+use std::f64::consts::PI;
+
+use crate::constants::{LINEAR_SLOP, MAX_LINEAR_CORRECTION};
+use crate::custom_math::{cross_s_v, cross_v_v};
+use crate::object::Object;
+use crate::scene::BodyHandle;
+use crate::types::Hertz;
+
+/// The stiffness of a spring-mode distance joint.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Spring {
+    /// The frequency at which the joint oscillates around its rest length when undamped.
+    pub frequency: Hertz,
+    /// How quickly oscillations die out, where one is critical damping.
+    pub damping_ratio: f64,
+}
+
+/// Keeps an anchor point on one body at a given distance from an anchor point on another.
+///
+/// Without a spring or limits the joint acts as a rigid rod. Limits alone let the length move
+/// freely between them, like a rope when only the maximum is set. A spring pulls the length
+/// towards the rest length, while any limits still hold rigidly.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DistanceJoint {
+    /// The first body.
+    a: BodyHandle,
+    /// The second body.
+    b: BodyHandle,
+    /// The anchor point relative to the center of the first body, in its local space.
+    pub local_anchor_a: Point2<f64>,
+    /// The anchor point relative to the center of the second body, in its local space.
+    pub local_anchor_b: Point2<f64>,
+    /// The rest length of the joint.
+    pub length: f64,
+    /// The shortest the joint may get, if limited. A minimum above the maximum is treated as
+    /// equal to the maximum.
+    pub min_length: Option<f64>,
+    /// The longest the joint may get, if limited.
+    pub max_length: Option<f64>,
+    /// Makes the joint springy instead of holding the rest length rigidly.
+    pub spring: Option<Spring>,
+    /// Whether the two bodies still collide with each other.
+    pub collide_connected: bool,
+    /// The anchor on `a` relative to its center, in world space, at the start of the step.
+    ra: Vector2<f64>,
+    /// The anchor on `b` relative to its center, in world space, at the start of the step.
+    rb: Vector2<f64>,
+    /// The direction from the anchor on `a` to the anchor on `b`.
+    u: Vector2<f64>,
+    /// The distance between the anchors at the start of the step.
+    current_length: f64,
+    /// The mass the joint moves along `u`, or zero if neither body can move.
+    axial_mass: f64,
+    /// The softness of the spring, added to the inverse axial mass.
+    gamma: f64,
+    /// The velocity the spring drives the length at, given how far it is stretched.
+    bias: f64,
+    /// The impulse applied this step by the spring or rod.
+    impulse: f64,
+    /// The impulse applied this step to keep the minimum length.
+    lower_impulse: f64,
+    /// The impulse applied this step to keep the maximum length.
+    upper_impulse: f64,
+}
+
+impl DistanceJoint {
+    /// Creates a new rigid `DistanceJoint` between two bodies.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The handle of the first body.
+    /// * `b` - The handle of the second body.
+    /// * `local_anchor_a` - The anchor point in the first body's local space.
+    /// * `local_anchor_b` - The anchor point in the second body's local space.
+    /// * `length` - The distance to keep between the anchors.
+    ///
+    /// # Returns
+    ///
+    /// A new `DistanceJoint` instance, with the bodies not colliding with each other.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `length` is negative.
+    pub fn new(
+        a: BodyHandle,
+        b: BodyHandle,
+        local_anchor_a: Point2<f64>,
+        local_anchor_b: Point2<f64>,
+        length: f64,
+    ) -> Self {
+        assert!(length >= 0.0, "joint length must not be negative");
+
+        DistanceJoint {
+            a,
+            b,
+            local_anchor_a,
+            local_anchor_b,
+            length,
+            min_length: None,
+            max_length: None,
+            spring: None,
+            collide_connected: false,
+            ra: Vector2::zeros(),
+            rb: Vector2::zeros(),
+            u: Vector2::zeros(),
+            current_length: 0.0,
+            axial_mass: 0.0,
+            gamma: 0.0,
+            bias: 0.0,
+            impulse: 0.0,
+            lower_impulse: 0.0,
+            upper_impulse: 0.0,
+        }
+    }
+
+    /// Limits how short the joint may get.
+    ///
+    /// A maximum set earlier that is below the new minimum is raised to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min_length` is negative.
+    pub fn with_min_length(mut self, min_length: f64) -> Self {
+        assert!(min_length >= 0.0, "joint length must not be negative");
+        self.min_length = Some(min_length);
+        self.max_length = self.max_length.map(|max_length| max_length.max(min_length));
+        self
+    }
+
+    /// Limits how long the joint may get.
+    ///
+    /// A minimum set earlier that is above the new maximum is lowered to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_length` is negative.
+    pub fn with_max_length(mut self, max_length: f64) -> Self {
+        assert!(max_length >= 0.0, "joint length must not be negative");
+        self.max_length = Some(max_length);
+        self.min_length = self.min_length.map(|min_length| min_length.min(max_length));
+        self
+    }
+
+    /// Turns the joint into a spring around its rest length.
+    ///
+    /// # Arguments
+    ///
+    /// * `frequency` - The undamped oscillation frequency.
+    /// * `damping_ratio` - How quickly oscillations die out, where one is critical damping.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frequency` is not positive or `damping_ratio` is negative.
+    pub fn with_spring(mut self, frequency: Hertz, damping_ratio: f64) -> Self {
+        assert!(frequency > 0.0, "spring frequency must be positive");
+        assert!(damping_ratio >= 0.0, "damping ratio must not be negative");
+        self.spring = Some(Spring {
+            frequency,
+            damping_ratio,
+        });
+        self
+    }
+
+    /// Sets whether the two bodies still collide with each other.
+    pub fn with_collide_connected(mut self, collide_connected: bool) -> Self {
+        self.collide_connected = collide_connected;
+        self
+    }
+
+    /// Returns the handle of the first body.
+    pub fn a(&self) -> BodyHandle {
+        self.a
+    }
+
+    /// Returns the handle of the second body.
+    pub fn b(&self) -> BodyHandle {
+        self.b
+    }
+
+    /// Returns the world-space anchor points on the two bodies.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The object referred to by `self.a`.
+    /// * `b` - The object referred to by `self.b`.
+    pub fn world_anchors(&self, a: &Object, b: &Object) -> (Point2<f64>, Point2<f64>) {
+        (
+            a.tx.to_world(&self.local_anchor_a),
+            b.tx.to_world(&self.local_anchor_b),
+        )
+    }
+
+    /// Returns the distance between the anchor points of the two bodies.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The object referred to by `self.a`.
+    /// * `b` - The object referred to by `self.b`.
+    pub fn current_length(&self, a: &Object, b: &Object) -> f64 {
+        let (pa, pb) = self.world_anchors(a, b);
+        (pb - pa).norm()
+    }
+
+    /// Returns the minimum length the solver uses, which never exceeds the maximum, so that the
+    /// two limits cannot push against each other.
+    fn effective_min_length(&self) -> Option<f64> {
+        match (self.min_length, self.max_length) {
+            (Some(min_length), Some(max_length)) => Some(min_length.min(max_length)),
+            (min_length, _) => min_length,
+        }
+    }
+
+    /// Returns `true` if the joint holds its rest length rigidly.
+    fn is_rigid(&self) -> bool {
+        self.spring.is_none() && self.min_length.is_none() && self.max_length.is_none()
+    }
+
+    /// Prepares the joint for the solver from the bodies' positions at the start of the step.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The object referred to by `self.a`.
+    /// * `b` - The object referred to by `self.b`.
+    /// * `dt` - The time step for the simulation.
+    pub fn initialize(&mut self, a: &Object, b: &Object, dt: f64) {
+        let (pa, pb) = self.world_anchors(a, b);
+        self.ra = pa - a.tx.pos;
+        self.rb = pb - b.tx.pos;
+        let d = pb - pa;
+        self.current_length = d.norm();
+        self.u = if self.current_length > LINEAR_SLOP {
+            d / self.current_length
+        } else {
+            Vector2::zeros()
+        };
+
+        let ra_cross_u = cross_v_v(&self.ra, &self.u);
+        let rb_cross_u = cross_v_v(&self.rb, &self.u);
+        let inv_mass_sum = a.inv_mass()
+            + b.inv_mass()
+            + (ra_cross_u * ra_cross_u) * a.inv_m_inertia()
+            + (rb_cross_u * rb_cross_u) * b.inv_m_inertia();
+        self.axial_mass = if inv_mass_sum > 0.0 {
+            1.0 / inv_mass_sum
+        } else {
+            0.0
+        };
+
+        // Soft constraint from the spring and damper constants the frequency gives for this mass,
+        // integrated implicitly so that stiff springs stay stable
+        self.gamma = 0.0;
+        self.bias = 0.0;
+        if let Some(spring) = self.spring {
+            let omega = 2.0 * PI * spring.frequency;
+            let stiffness = self.axial_mass * omega * omega;
+            let damping = 2.0 * self.axial_mass * spring.damping_ratio * omega;
+            let softness = dt * (damping + dt * stiffness);
+            if softness > 0.0 {
+                self.gamma = 1.0 / softness;
+                self.bias = (self.current_length - self.length) * dt * stiffness * self.gamma;
+            }
+        }
+
+        self.impulse = 0.0;
+        self.lower_impulse = 0.0;
+        self.upper_impulse = 0.0;
+    }
+
+    /// Applies an impulse along the joint so that the bodies' velocities respect it.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The object referred to by `self.a`.
+    /// * `b` - The object referred to by `self.b`.
+    /// * `dt` - The time step for the simulation.
+    pub fn apply_impulse(&mut self, a: &mut Object, b: &mut Object, dt: f64) {
+        if self.axial_mass == 0.0 {
+            return;
+        }
+
+        if self.is_rigid() {
+            let imp_s = -self.axial_mass * self.axial_speed(a, b);
+            self.apply_axial(a, b, imp_s);
+            self.impulse += imp_s;
+            return;
+        }
+
+        if self.spring.is_some() {
+            let inv_mass_sum = 1.0 / self.axial_mass + self.gamma;
+            let imp_s =
+                -(self.axial_speed(a, b) + self.bias + self.gamma * self.impulse) / inv_mass_sum;
+            self.apply_axial(a, b, imp_s);
+            self.impulse += imp_s;
+        }
+
+        // The limits only push once the length would pass them within this step
+        if let Some(min_length) = self.effective_min_length() {
+            let gap = (self.current_length - min_length).max(0.0);
+            let imp_s = -self.axial_mass * (self.axial_speed(a, b) + gap / dt);
+            let total = (self.lower_impulse + imp_s).max(0.0);
+            self.apply_axial(a, b, total - self.lower_impulse);
+            self.lower_impulse = total;
+        }
+
+        if let Some(max_length) = self.max_length {
+            let gap = (max_length - self.current_length).max(0.0);
+            let imp_s = -self.axial_mass * (-self.axial_speed(a, b) + gap / dt);
+            let total = (self.upper_impulse + imp_s).max(0.0);
+            self.apply_axial(a, b, self.upper_impulse - total);
+            self.upper_impulse = total;
+        }
+    }
+
+    /// Moves the bodies back within the rigid length or the limits, after their velocities have
+    /// been integrated.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The object referred to by `self.a`.
+    /// * `b` - The object referred to by `self.b`.
+    pub fn positional_correction(&mut self, a: &mut Object, b: &mut Object) {
+        let (pa, pb) = self.world_anchors(a, b);
+        let ra = pa - a.tx.pos;
+        let rb = pb - b.tx.pos;
+        let d = pb - pa;
+        let length = d.norm();
+        if length <= LINEAR_SLOP {
+            return;
+        }
+
+        let target = if self.is_rigid() {
+            self.length
+        } else {
+            length
+                .max(self.effective_min_length().unwrap_or(length))
+                .min(self.max_length.unwrap_or(length))
+        };
+        let error = (length - target).clamp(-MAX_LINEAR_CORRECTION, MAX_LINEAR_CORRECTION);
+        if error.abs() <= LINEAR_SLOP {
+            return;
+        }
+
+        let u = d / length;
+        let ra_cross_u = cross_v_v(&ra, &u);
+        let rb_cross_u = cross_v_v(&rb, &u);
+        let inv_mass_sum = a.inv_mass()
+            + b.inv_mass()
+            + (ra_cross_u * ra_cross_u) * a.inv_m_inertia()
+            + (rb_cross_u * rb_cross_u) * b.inv_m_inertia();
+        if inv_mass_sum == 0.0 {
+            return;
+        }
+
+        let correction = u * (-error / inv_mass_sum);
+        a.tx.pos -= correction * a.inv_mass();
+        a.tx.rotate(-a.inv_m_inertia() * cross_v_v(&ra, &correction));
+        b.tx.pos += correction * b.inv_mass();
+        b.tx.rotate(b.inv_m_inertia() * cross_v_v(&rb, &correction));
+    }
+
+    /// Returns how fast the anchors move apart along the joint.
+    fn axial_speed(&self, a: &Object, b: &Object) -> f64 {
+        let va = a.kinematics.vel + cross_s_v(a.kinematics.angular_vel, &self.ra);
+        let vb = b.kinematics.vel + cross_s_v(b.kinematics.angular_vel, &self.rb);
+        (vb - va).dot(&self.u)
+    }
+
+    /// Pushes the anchors apart along the joint with the given impulse, or together if negative.
+    fn apply_axial(&self, a: &mut Object, b: &mut Object, imp_s: f64) {
+        let imp = self.u * imp_s;
+        a.apply_impulse(&-imp, &self.ra);
+        b.apply_impulse(&imp, &self.rb);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use nalgebra::Point2;
+
+    use crate::{
+        circle::Circle, object::BodyType, scene::Scene, shapes::Shapes,
+        world_settings::WorldSettings,
+    };
+
+    const DT: f64 = 1.0 / 60.0;
+
+    /// Adds a static pivot at the origin and a ball at `pos`, and returns their handles.
+    fn pivot_and_ball(scene: &mut Scene, pos: Point2<f64>) -> (BodyHandle, BodyHandle) {
+        let pivot = scene.add_body(
+            Object::builder(Shapes::Circle(Circle::new(0.1).unwrap()))
+                .body_type(BodyType::Static)
+                .build(),
+        );
+        let ball = scene.add_body(
+            Object::builder(Shapes::Circle(Circle::new(0.5).unwrap()))
+                .position(pos)
+                .build(),
+        );
+
+        (pivot, ball)
+    }
+
+    /// Returns the distance of the ball from the pivot.
+    fn distance(scene: &Scene, pivot: BodyHandle, ball: BodyHandle) -> f64 {
+        (scene.body(ball).unwrap().tx.pos - scene.body(pivot).unwrap().tx.pos).norm()
+    }
+
+    #[test]
+    fn test_pendulum_keeps_its_length() {
+        let mut scene = Scene::new();
+        let (pivot, ball) = pivot_and_ball(&mut scene, Point2::new(3.0, 0.0));
+        scene
+            .add_joint(DistanceJoint::new(
+                pivot,
+                ball,
+                Point2::origin(),
+                Point2::origin(),
+                3.0,
+            ))
+            .unwrap();
+
+        let mut lowest = f64::NEG_INFINITY;
+        let mut leftmost = f64::INFINITY;
+        for _ in 0..300 {
+            scene.step(DT);
+
+            let length = distance(&scene, pivot, ball);
+            assert!((length - 3.0).abs() < 0.01, "length drifted to {length}");
+
+            let pos = scene.body(ball).unwrap().tx.pos;
+            lowest = lowest.max(pos.y);
+            leftmost = leftmost.min(pos.x);
+        }
+
+        // It swings through the bottom and up the other side
+        assert!(lowest > 2.9, "only swung down to {lowest}");
+        assert!(leftmost < -2.0, "only swung out to {leftmost}");
+    }
+
+    #[test]
+    fn test_spring_oscillates_at_its_frequency() {
+        let mut scene = Scene::with_settings(WorldSettings {
+            gravity: Vector2::zeros(),
+            ..WorldSettings::default()
+        });
+        let (pivot, ball) = pivot_and_ball(&mut scene, Point2::new(2.5, 0.0));
+        scene
+            .add_joint(
+                DistanceJoint::new(pivot, ball, Point2::origin(), Point2::origin(), 2.0)
+                    .with_spring(1.0, 0.0),
+            )
+            .unwrap();
+
+        // Time the stretch crossing zero on the way up, interpolating within the step
+        let mut crossings = Vec::new();
+        let mut prev = distance(&scene, pivot, ball) - 2.0;
+        for i in 1..=300 {
+            scene.step(DT);
+            let stretch = distance(&scene, pivot, ball) - 2.0;
+            if prev < 0.0 && stretch >= 0.0 {
+                crossings.push((i as f64 - stretch / (stretch - prev)) * DT);
+            }
+            prev = stretch;
+        }
+
+        assert!(
+            crossings.len() >= 4,
+            "only crossed {} times",
+            crossings.len()
+        );
+        let period = (crossings[crossings.len() - 1] - crossings[0]) / (crossings.len() - 1) as f64;
+        assert!((period - 1.0).abs() < 0.02, "period was {period}");
+    }
+
+    #[test]
+    fn test_max_length_acts_as_a_rope() {
+        let mut scene = Scene::new();
+        let (pivot, ball) = pivot_and_ball(&mut scene, Point2::new(0.0, 1.0));
+        scene
+            .add_joint(
+                DistanceJoint::new(pivot, ball, Point2::origin(), Point2::origin(), 1.0)
+                    .with_max_length(2.0),
+            )
+            .unwrap();
+
+        // The ball falls freely until the rope is taut, then hangs from it
+        for _ in 0..10 {
+            scene.step(DT);
+        }
+        assert!(scene.body(ball).unwrap().tx.pos.y > 1.1);
+
+        for _ in 0..110 {
+            scene.step(DT);
+        }
+        let length = distance(&scene, pivot, ball);
+        assert!((length - 2.0).abs() < 0.01, "hanging at {length}");
+    }
+
+    #[test]
+    fn test_crossed_limits_are_reconciled() {
+        let (a, b) = (BodyHandle::from_slot(0), BodyHandle::from_slot(1));
+        let joint = |length| DistanceJoint::new(a, b, Point2::origin(), Point2::origin(), length);
+
+        // The limit set last wins
+        let lowered = joint(1.0).with_min_length(3.0).with_max_length(2.0);
+        assert_eq!(
+            (lowered.min_length, lowered.max_length),
+            (Some(2.0), Some(2.0))
+        );
+        let raised = joint(1.0).with_max_length(2.0).with_min_length(3.0);
+        assert_eq!(
+            (raised.min_length, raised.max_length),
+            (Some(3.0), Some(3.0))
+        );
+
+        // Crossed limits written directly hold at the maximum instead of fighting
+        let mut scene = Scene::with_settings(WorldSettings {
+            gravity: Vector2::zeros(),
+            ..WorldSettings::default()
+        });
+        let (pivot, ball) = pivot_and_ball(&mut scene, Point2::new(1.0, 0.0));
+        let mut crossed = DistanceJoint::new(pivot, ball, Point2::origin(), Point2::origin(), 1.0);
+        crossed.min_length = Some(3.0);
+        crossed.max_length = Some(2.0);
+        scene.add_joint(crossed).unwrap();
+
+        for _ in 0..120 {
+            scene.step(DT);
+        }
+        let length = distance(&scene, pivot, ball);
+        assert!((length - 2.0).abs() < 0.01, "settled at {length}");
+        let speed = scene.body(ball).unwrap().kinematics.vel.norm();
+        assert!(speed < 0.1, "still moving at {speed}");
+    }
+}
@@ -0,0 +1,493 @@
+use crate::aabb::Aabb;
+
+/// Identifies a leaf of a `DynamicTree`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ProxyId(usize);
+
+/// A node of a `DynamicTree`, either a leaf holding user data or a branch with two children.
+struct Node<T> {
+    /// The bounding box of the node; fattened for leaves, the union of the children for branches.
+    aabb: Aabb,
+    /// The parent of the node, `None` for the root.
+    parent: Option<usize>,
+    /// The children of a branch, `None` for leaves.
+    children: Option<[usize; 2]>,
+    /// The user data of a leaf, `None` for branches.
+    data: Option<T>,
+    /// The height of the subtree below the node, zero for leaves.
+    height: usize,
+}
+
+// Adapted from the dynamic tree in Box2D by Erin Catto
+/// A bounding volume hierarchy over fattened axis-aligned bounding boxes.
+///
+/// Leaves store a box grown by a margin around the tight box they were given, so that objects
+/// which move a little do not need to be reinserted every step. Insertion picks the sibling that
+/// grows the tree's total perimeter the least, and rotations keep the tree balanced.
+pub struct DynamicTree<T> {
+    /// All nodes, live or free.
+    nodes: Vec<Option<Node<T>>>,
+    /// Nodes that can be reused.
+    free: Vec<usize>,
+    /// The root of the tree, `None` when empty.
+    root: Option<usize>,
+    /// Number of leaves.
+    len: usize,
+}
+
+impl<T> Default for DynamicTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> DynamicTree<T> {
+    /// Creates a new, empty tree.
+    pub fn new() -> Self {
+        DynamicTree {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            root: None,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of leaves in the tree.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the tree has no leaves.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the height of the tree, zero when it holds at most one leaf.
+    pub fn height(&self) -> usize {
+        self.root.map_or(0, |root| self.node(root).height)
+    }
+
+    /// Adds a leaf to the tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `aabb` - The tight bounding box of the leaf.
+    /// * `margin` - How far to fatten the box on every side.
+    /// * `data` - The user data stored in the leaf.
+    ///
+    /// # Returns
+    ///
+    /// The id of the new leaf.
+    pub fn create_proxy(&mut self, aabb: Aabb, margin: f64, data: T) -> ProxyId {
+        let leaf = self.allocate(Node {
+            aabb: aabb.fattened(margin),
+            parent: None,
+            children: None,
+            data: Some(data),
+            height: 0,
+        });
+
+        self.insert_leaf(leaf);
+        self.len += 1;
+
+        ProxyId(leaf)
+    }
+
+    /// Removes a leaf from the tree.
+    ///
+    /// # Returns
+    ///
+    /// The user data of the leaf.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the id does not refer to a leaf of this tree.
+    pub fn destroy_proxy(&mut self, id: ProxyId) -> T {
+        self.remove_leaf(id.0);
+        self.len -= 1;
+
+        let node = self.release(id.0);
+        node.data.expect("proxy id refers to a branch")
+    }
+
+    /// Updates the bounding box of a leaf.
+    ///
+    /// Nothing happens while the tight box stays inside the leaf's fattened box.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the leaf.
+    /// * `aabb` - The new tight bounding box.
+    /// * `margin` - How far to fatten the box on every side if the leaf is reinserted.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the leaf was reinserted.
+    pub fn move_proxy(&mut self, id: ProxyId, aabb: Aabb, margin: f64) -> bool {
+        if self.node(id.0).aabb.contains(&aabb) {
+            return false;
+        }
+
+        self.remove_leaf(id.0);
+        self.node_mut(id.0).aabb = aabb.fattened(margin);
+        self.insert_leaf(id.0);
+
+        true
+    }
+
+    /// Returns the fattened bounding box of a leaf.
+    pub fn fat_aabb(&self, id: ProxyId) -> &Aabb {
+        &self.node(id.0).aabb
+    }
+
+    /// Returns the user data of a leaf.
+    pub fn data(&self, id: ProxyId) -> &T {
+        self.node(id.0)
+            .data
+            .as_ref()
+            .expect("proxy id refers to a branch")
+    }
+
+    /// Calls `f` for every leaf whose fattened box overlaps the given box.
+    ///
+    /// # Arguments
+    ///
+    /// * `aabb` - The box to test against.
+    /// * `f` - The callback, given the id of each overlapping leaf.
+    pub fn query<F: FnMut(ProxyId)>(&self, aabb: &Aabb, mut f: F) {
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+
+        while let Some(index) = stack.pop() {
+            let node = self.node(index);
+            if !node.aabb.overlaps(aabb) {
+                continue;
+            }
+
+            match node.children {
+                Some(children) => stack.extend(children),
+                None => f(ProxyId(index)),
+            }
+        }
+    }
+
+    /// Returns the node at the given index.
+    fn node(&self, index: usize) -> &Node<T> {
+        self.nodes[index].as_ref().expect("node index is free")
+    }
+
+    /// Returns the node at the given index mutably.
+    fn node_mut(&mut self, index: usize) -> &mut Node<T> {
+        self.nodes[index].as_mut().expect("node index is free")
+    }
+
+    /// Stores a node, reusing a free slot if there is one.
+    fn allocate(&mut self, node: Node<T>) -> usize {
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = Some(node);
+                index
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    /// Frees a node and returns it.
+    fn release(&mut self, index: usize) -> Node<T> {
+        self.free.push(index);
+        self.nodes[index].take().expect("node index is free")
+    }
+
+    /// Replaces `old` with `new` among the children of `parent`, or as the root if there is none.
+    fn replace_child(&mut self, parent: Option<usize>, old: usize, new: usize) {
+        match parent {
+            Some(parent) => {
+                let children = self
+                    .node_mut(parent)
+                    .children
+                    .as_mut()
+                    .expect("parent is a leaf");
+                let slot = if children[0] == old { 0 } else { 1 };
+                children[slot] = new;
+            }
+            None => self.root = Some(new),
+        }
+    }
+
+    /// Inserts a detached leaf next to the sibling that grows the tree the least.
+    fn insert_leaf(&mut self, leaf: usize) {
+        let Some(root) = self.root else {
+            self.root = Some(leaf);
+            self.node_mut(leaf).parent = None;
+            return;
+        };
+
+        let leaf_aabb = self.node(leaf).aabb;
+
+        // Find the best sibling
+        let mut index = root;
+        while let Some([c1, c2]) = self.node(index).children {
+            let aabb = self.node(index).aabb;
+            let perimeter = aabb.perimeter();
+            let combined = aabb.union(&leaf_aabb).perimeter();
+
+            // Cost of making a new parent for this node and the leaf
+            let cost = 2.0 * combined;
+            // Minimum cost of pushing the leaf further down the tree
+            let inheritance = 2.0 * (combined - perimeter);
+
+            let child_cost = |child: usize| {
+                let node = self.node(child);
+                let union = node.aabb.union(&leaf_aabb).perimeter();
+                match node.children {
+                    None => union + inheritance,
+                    Some(_) => union - node.aabb.perimeter() + inheritance,
+                }
+            };
+            let (cost1, cost2) = (child_cost(c1), child_cost(c2));
+
+            if cost < cost1 && cost < cost2 {
+                break;
+            }
+
+            index = if cost1 < cost2 { c1 } else { c2 };
+        }
+
+        let sibling = index;
+        let old_parent = self.node(sibling).parent;
+        let new_parent = self.allocate(Node {
+            aabb: self.node(sibling).aabb.union(&leaf_aabb),
+            parent: old_parent,
+            children: Some([sibling, leaf]),
+            data: None,
+            height: self.node(sibling).height + 1,
+        });
+
+        self.replace_child(old_parent, sibling, new_parent);
+        self.node_mut(sibling).parent = Some(new_parent);
+        self.node_mut(leaf).parent = Some(new_parent);
+
+        self.refit(Some(new_parent));
+    }
+
+    /// Detaches a leaf from the tree, freeing its parent.
+    fn remove_leaf(&mut self, leaf: usize) {
+        if self.root == Some(leaf) {
+            self.root = None;
+            return;
+        }
+
+        let parent = self.node(leaf).parent.expect("non-root leaf has a parent");
+        let grand_parent = self.node(parent).parent;
+        let [c1, c2] = self.node(parent).children.expect("parent is a leaf");
+        let sibling = if c1 == leaf { c2 } else { c1 };
+
+        self.replace_child(grand_parent, parent, sibling);
+        self.node_mut(sibling).parent = grand_parent;
+        self.node_mut(leaf).parent = None;
+        self.release(parent);
+
+        self.refit(grand_parent);
+    }
+
+    /// Walks from `index` up to the root, rebalancing and updating boxes and heights.
+    fn refit(&mut self, mut index: Option<usize>) {
+        while let Some(i) = index {
+            let i = self.balance(i);
+            let [c1, c2] = self.node(i).children.expect("refitting a leaf");
+
+            let aabb = self.node(c1).aabb.union(&self.node(c2).aabb);
+            let height = 1 + self.node(c1).height.max(self.node(c2).height);
+            let node = self.node_mut(i);
+            node.aabb = aabb;
+            node.height = height;
+
+            index = node.parent;
+        }
+    }
+
+    /// Rotates a child up if the subtree at `a` is out of balance.
+    ///
+    /// # Returns
+    ///
+    /// The index of the new root of the subtree.
+    fn balance(&mut self, a: usize) -> usize {
+        let Some([b, c]) = self.node(a).children else {
+            return a;
+        };
+        if self.node(a).height < 2 {
+            return a;
+        }
+
+        let balance = self.node(c).height as isize - self.node(b).height as isize;
+
+        if balance > 1 {
+            self.rotate_up(a, c, b)
+        } else if balance < -1 {
+            self.rotate_up(a, b, c)
+        } else {
+            a
+        }
+    }
+
+    /// Makes `up`, a child of `a`, the parent of `a`, with `a` keeping `stay` as a child.
+    ///
+    /// The taller child of `up` stays with `up`, while the shorter one moves to `a`.
+    ///
+    /// # Returns
+    ///
+    /// The index of `up`, the new root of the subtree.
+    fn rotate_up(&mut self, a: usize, up: usize, stay: usize) -> usize {
+        let [f, g] = self.node(up).children.expect("rotating up a leaf");
+        let (keep, give) = if self.node(f).height > self.node(g).height {
+            (f, g)
+        } else {
+            (g, f)
+        };
+
+        let parent = self.node(a).parent;
+        self.replace_child(parent, a, up);
+        self.node_mut(up).parent = parent;
+        self.node_mut(a).parent = Some(up);
+        self.node_mut(give).parent = Some(a);
+
+        let a_aabb = self.node(stay).aabb.union(&self.node(give).aabb);
+        let a_height = 1 + self.node(stay).height.max(self.node(give).height);
+        let a_node = self.node_mut(a);
+        a_node.children = Some([stay, give]);
+        a_node.aabb = a_aabb;
+        a_node.height = a_height;
+
+        let up_aabb = a_aabb.union(&self.node(keep).aabb);
+        let up_height = 1 + a_height.max(self.node(keep).height);
+        let up_node = self.node_mut(up);
+        up_node.children = Some([a, keep]);
+        up_node.aabb = up_aabb;
+        up_node.height = up_height;
+
+        up
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use nalgebra::Point2;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    /// Checks the parent links, boxes and heights of the whole tree.
+    fn check_structure<T>(tree: &DynamicTree<T>) {
+        let mut leaves = 0;
+        let mut stack: Vec<(usize, Option<usize>)> =
+            tree.root.into_iter().map(|r| (r, None)).collect();
+
+        while let Some((index, parent)) = stack.pop() {
+            let node = tree.node(index);
+            assert_eq!(node.parent, parent);
+
+            match node.children {
+                Some([c1, c2]) => {
+                    let (n1, n2) = (tree.node(c1), tree.node(c2));
+                    assert!(node.aabb.contains(&n1.aabb) && node.aabb.contains(&n2.aabb));
+                    assert_eq!(node.height, 1 + n1.height.max(n2.height));
+                    assert!(n1.height.abs_diff(n2.height) <= 1);
+                    stack.push((c1, Some(index)));
+                    stack.push((c2, Some(index)));
+                }
+                None => {
+                    assert_eq!(node.height, 0);
+                    leaves += 1;
+                }
+            }
+        }
+
+        assert_eq!(leaves, tree.len());
+    }
+
+    fn random_aabb(rng: &mut StdRng) -> Aabb {
+        let min = Point2::new(rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0));
+        let size = Point2::new(rng.gen_range(0.1..5.0), rng.gen_range(0.1..5.0));
+
+        Aabb::new(min, min + size.coords)
+    }
+
+    #[test]
+    fn test_query_matches_brute_force() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut tree = DynamicTree::new();
+        let mut proxies: Vec<(ProxyId, Aabb)> = Vec::new();
+
+        for i in 0..200 {
+            let aabb = random_aabb(&mut rng);
+            proxies.push((tree.create_proxy(aabb, 0.0, i), aabb));
+        }
+
+        // Move some, remove some
+        for _ in 0..100 {
+            let i = rng.gen_range(0..proxies.len());
+            let aabb = random_aabb(&mut rng);
+            tree.move_proxy(proxies[i].0, aabb, 0.0);
+            proxies[i].1 = aabb;
+        }
+        for _ in 0..50 {
+            let (id, _) = proxies.swap_remove(rng.gen_range(0..proxies.len()));
+            tree.destroy_proxy(id);
+        }
+
+        check_structure(&tree);
+
+        for _ in 0..50 {
+            let query = random_aabb(&mut rng).fattened(5.0);
+
+            let mut found = Vec::new();
+            tree.query(&query, |id| found.push(*tree.data(id)));
+            found.sort();
+
+            let mut expected: Vec<i32> = proxies
+                .iter()
+                .filter(|(_, aabb)| aabb.overlaps(&query))
+                .map(|(id, _)| *tree.data(*id))
+                .collect();
+            expected.sort();
+
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn test_sorted_insertions_stay_balanced() {
+        let mut tree = DynamicTree::new();
+
+        for i in 0..1024 {
+            let min = Point2::new(i as f64, 0.0);
+            tree.create_proxy(
+                Aabb::new(min, min + nalgebra::Vector2::new(0.5, 0.5)),
+                0.0,
+                i,
+            );
+        }
+
+        check_structure(&tree);
+        // A perfectly balanced tree of 1024 leaves has a height of 10
+        assert!(tree.height() <= 20, "height {}", tree.height());
+    }
+
+    #[test]
+    fn test_small_moves_stay_in_fat_box() {
+        let mut tree = DynamicTree::new();
+        let aabb = Aabb::new(Point2::new(0.0, 0.0), Point2::new(1.0, 1.0));
+        let id = tree.create_proxy(aabb, 0.5, ());
+
+        let nudged = Aabb::new(Point2::new(0.2, 0.2), Point2::new(1.2, 1.2));
+        assert!(!tree.move_proxy(id, nudged, 0.5));
+        assert_eq!(*tree.fat_aabb(id), aabb.fattened(0.5));
+
+        let moved = Aabb::new(Point2::new(2.0, 0.0), Point2::new(3.0, 1.0));
+        assert!(tree.move_proxy(id, moved, 0.5));
+        assert_eq!(*tree.fat_aabb(id), moved.fattened(0.5));
+    }
+}
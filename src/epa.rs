@@ -0,0 +1,255 @@
+use nalgebra::{Point2, Vector2};
+
+use crate::constants::{EPA_MAX_ITERATIONS, EPA_TOLERANCE, GEOMETRY_TOLERANCE};
+use crate::custom_math::cross_v_v;
+use crate::gjk::{gjk, Simplex};
+use crate::support_map::SupportMap;
+use crate::transform::Transform;
+
+/// Finds how deeply two convex shapes overlap, using GJK to detect the overlap and EPA to
+/// measure it.
+///
+/// # Arguments
+///
+/// * `a` - The first shape.
+/// * `tx_a` - The transform that brings the first shape into world space.
+/// * `b` - The second shape.
+/// * `tx_b` - The transform that brings the second shape into world space.
+///
+/// # Returns
+///
+/// The unit normal pointing from `a` towards `b` and the depth of the overlap along it, or
+/// `None` if the shapes do not overlap. Moving `b` by the depth along the normal separates them.
+pub fn penetration<A, B>(
+    a: &A,
+    tx_a: &Transform,
+    b: &B,
+    tx_b: &Transform,
+) -> Option<(Vector2<f64>, f64)>
+where
+    A: SupportMap + ?Sized,
+    B: SupportMap + ?Sized,
+{
+    let support_a = |d: &Vector2<f64>| a.support(tx_a, d);
+    let support_b = |d: &Vector2<f64>| b.support(tx_b, d);
+
+    let start = tx_b.pos - tx_a.pos;
+    let start = if start.norm_squared() > 0.0 {
+        start
+    } else {
+        Vector2::x()
+    };
+
+    let simplex = gjk(support_a, support_b, &start);
+    let triangle = match simplex.count {
+        3 => simplex.vertices.map(|v| v.w),
+        _ if simplex.closest_point().norm() > GEOMETRY_TOLERANCE => return None,
+        _ => blow_up(support_a, support_b, &simplex)?,
+    };
+
+    let (normal, depth) = epa(support_a, support_b, triangle)?;
+    if depth <= GEOMETRY_TOLERANCE {
+        return None;
+    }
+
+    // The origin leaves `b - a` through the face with this normal when `b` moves against it
+    Some((-normal, depth))
+}
+
+/// Grows a simplex that ended with the origin on a point or a segment into a triangle.
+///
+/// GJK stops as soon as the origin lies on the simplex, which happens both when the shapes only
+/// touch and when they overlap with their supports lined up, such as two circles directly above
+/// one another. The difference is only flat in the first case.
+///
+/// # Arguments
+///
+/// * `support_a` - Returns the world space point of `a` furthest along a direction.
+/// * `support_b` - Returns the world space point of `b` furthest along a direction.
+/// * `simplex` - The point or segment GJK ended with, which contains the origin.
+///
+/// # Returns
+///
+/// A triangle of points of the difference `b - a` with the origin on its boundary, or `None` if
+/// the difference is too flat to hold one.
+fn blow_up<FA, FB>(support_a: FA, support_b: FB, simplex: &Simplex) -> Option<[Vector2<f64>; 3]>
+where
+    FA: Fn(&Vector2<f64>) -> Point2<f64>,
+    FB: Fn(&Vector2<f64>) -> Point2<f64>,
+{
+    let support = |d: &Vector2<f64>| support_b(d) - support_a(&-d);
+
+    // Pick whichever of two opposite supports lies furthest from the line through `from`
+    let furthest = |from: Vector2<f64>, n: Vector2<f64>| {
+        let (p1, p2) = (support(&n), support(&-n));
+        let (d1, d2) = (n.dot(&(p1 - from)).abs(), n.dot(&(p2 - from)).abs());
+
+        if d1 >= d2 {
+            (p1, d1)
+        } else {
+            (p2, d2)
+        }
+    };
+
+    let w1 = simplex.vertices[0].w;
+    let w2 = if simplex.count == 2 {
+        simplex.vertices[1].w
+    } else {
+        let (p, d) = furthest(w1, Vector2::x());
+        let (q, e) = furthest(w1, Vector2::y());
+        if d.max(e) <= GEOMETRY_TOLERANCE {
+            return None;
+        }
+
+        if d >= e {
+            p
+        } else {
+            q
+        }
+    };
+
+    let e = (w2 - w1).normalize();
+    let (w3, d) = furthest(w1, Vector2::new(e.y, -e.x));
+    if d <= GEOMETRY_TOLERANCE {
+        return None;
+    }
+
+    Some([w1, w2, w3])
+}
+
+// Adapted from the description in https://dyn4j.org/2010/05/epa-expanding-polytope-algorithm/
+/// Expands a triangle inside the Minkowski difference `b - a` until it finds the face of the
+/// difference closest to the origin.
+///
+/// # Arguments
+///
+/// * `support_a` - Returns the world space point of `a` furthest along a direction.
+/// * `support_b` - Returns the world space point of `b` furthest along a direction.
+/// * `triangle` - A triangle of points of the difference that contains the origin.
+///
+/// # Returns
+///
+/// The outward normal of the closest face and its distance from the origin, or `None` if the
+/// triangle is degenerate, which means the shapes only touch.
+fn epa<FA, FB>(
+    support_a: FA,
+    support_b: FB,
+    triangle: [Vector2<f64>; 3],
+) -> Option<(Vector2<f64>, f64)>
+where
+    FA: Fn(&Vector2<f64>) -> Point2<f64>,
+    FB: Fn(&Vector2<f64>) -> Point2<f64>,
+{
+    let mut polytope = triangle.to_vec();
+
+    // Keep the polytope counter-clockwise so that edge normals point outwards
+    let area = cross_v_v(&(polytope[1] - polytope[0]), &(polytope[2] - polytope[0]));
+    if area.abs() <= f64::EPSILON {
+        return None;
+    }
+    if area < 0.0 {
+        polytope.swap(1, 2);
+    }
+
+    let mut closest = (Vector2::zeros(), f64::INFINITY);
+
+    for _ in 0..EPA_MAX_ITERATIONS {
+        // Find the edge closest to the origin
+        let mut edge = 0;
+        closest.1 = f64::INFINITY;
+
+        for i in 0..polytope.len() {
+            let e = polytope[(i + 1) % polytope.len()] - polytope[i];
+            let Some(n) = Vector2::new(e.y, -e.x).try_normalize(f64::EPSILON) else {
+                continue;
+            };
+
+            let dist = n.dot(&polytope[i]);
+            if dist < closest.1 {
+                closest = (n, dist);
+                edge = i;
+            }
+        }
+
+        // Stop once the difference reaches no further out than the edge
+        let n = closest.0;
+        let p = support_b(&n) - support_a(&-n);
+        if n.dot(&p) - closest.1 <= EPA_TOLERANCE {
+            break;
+        }
+
+        polytope.insert(edge + 1, p);
+    }
+
+    Some(closest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::circle::Circle;
+    use crate::polygon::Polygon;
+
+    fn square(half: f64) -> Polygon {
+        Polygon::new(vec![
+            Point2::new(-half, -half),
+            Point2::new(half, -half),
+            Point2::new(half, half),
+            Point2::new(-half, half),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_circles() {
+        let a = Circle::new(1.0).unwrap();
+        let b = Circle::new(0.5).unwrap();
+        let tx_a = Transform::new(Point2::new(1.0, 1.0));
+        let tx_b = Transform::new(Point2::new(1.6, 1.8));
+
+        let (normal, depth) = penetration(&a, &tx_a, &b, &tx_b).unwrap();
+        // Curved shapes are only refined until the depth is accurate, which leaves the normal a
+        // little less so
+        assert!((normal - Vector2::new(0.6, 0.8)).norm() < 1e-5);
+        assert!((depth - 0.5).abs() < 1e-6);
+
+        let far = Transform::new(Point2::new(3.0, 1.0));
+        assert!(penetration(&a, &tx_a, &b, &far).is_none());
+    }
+
+    #[test]
+    fn test_rotated_squares() {
+        let a = square(1.0);
+        let b = square(0.5);
+        let tx_a = Transform::default();
+        // Standing on a corner that dips 0.2 into the top face of `a`
+        let tx_b = Transform::with_orientation(
+            Point2::new(0.3, 1.0 + 0.5f64.sqrt() - 0.2),
+            std::f64::consts::FRAC_PI_4,
+        );
+
+        let (normal, depth) = penetration(&a, &tx_a, &b, &tx_b).unwrap();
+        assert!((normal - Vector2::new(0.0, 1.0)).norm() < 1e-9);
+        assert!((depth - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_lined_up_overlap() {
+        // Every support point GJK asks for lies on the vertical line through both centers
+        let a = Circle::new(1.0).unwrap();
+        let tx_b = Transform::new(Point2::new(0.0, 1.5));
+
+        let (normal, depth) = penetration(&a, &Transform::default(), &a, &tx_b).unwrap();
+        assert!((normal - Vector2::new(0.0, 1.0)).norm() < 1e-5);
+        assert!((depth - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_touching_is_not_overlapping() {
+        let a = square(1.0);
+        let tx_b = Transform::new(Point2::new(2.0, 0.5));
+
+        assert!(penetration(&a, &Transform::default(), &a, &tx_b).is_none());
+    }
+}
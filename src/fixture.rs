@@ -0,0 +1,248 @@
+use nalgebra::Vector2;
+
+use crate::collision_filter::CollisionFilter;
+use crate::mass_data::MassData;
+use crate::material::Material;
+use crate::shapes::{Shape, Shapes};
+use crate::transform::Transform;
+
+/// A shape attached to a body, with its own placement and material.
+///
+/// A body made of several fixtures moves as one rigid body; each fixture collides on its own
+/// while the resulting impulses go to the shared body.
+pub struct Fixture {
+    /// The shape of the fixture.
+    pub shape: Shapes,
+    /// The placement of the shape relative to the body's center of mass.
+    pub local: Transform,
+    /// The material of the fixture.
+    pub mat: Material,
+    /// Which other fixtures this one collides with.
+    pub filter: CollisionFilter,
+    /// Whether the fixture only detects overlaps, letting other bodies pass through it.
+    pub is_sensor: bool,
+}
+
+impl Fixture {
+    /// Creates a new fixture that collides with everything.
+    ///
+    /// # Arguments
+    ///
+    /// * `shape` - The shape of the fixture.
+    /// * `local` - The offset and rotation of the shape relative to the body.
+    /// * `mat` - The material of the fixture.
+    pub fn new(shape: Shapes, local: Transform, mat: Material) -> Self {
+        Fixture {
+            shape,
+            local,
+            mat,
+            filter: CollisionFilter::default(),
+            is_sensor: false,
+        }
+    }
+
+    /// Sets the collision filter of the fixture.
+    pub fn with_filter(mut self, filter: CollisionFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Sets whether the fixture is a sensor.
+    ///
+    /// A sensor reports which parts overlap it through the scene's sensor events, but never
+    /// pushes them apart.
+    pub fn with_sensor(mut self, is_sensor: bool) -> Self {
+        self.is_sensor = is_sensor;
+        self
+    }
+
+    /// Returns the transform that brings the fixture's shape into world space.
+    ///
+    /// # Arguments
+    ///
+    /// * `body_tx` - The transform of the body the fixture is attached to.
+    pub fn world_transform(&self, body_tx: &Transform) -> Transform {
+        body_tx.compose(&self.local)
+    }
+}
+
+/// Identifies one convex part of one of a body's fixtures.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PartId {
+    /// The index of the fixture in the body.
+    pub fixture: usize,
+    /// The index of the convex part in the fixture's shape.
+    pub part: usize,
+}
+
+/// Calculates the mass data of a body made of the given fixtures.
+///
+/// Every shape is recentered on its own centroid, as `Shape::calculate_mass_data` does, with the
+/// fixture's offset adjusted so that the shape stays in place. The fixtures are then moved
+/// together so that the combined center of mass sits at the body's origin, and the moments of
+/// inertia are combined using the parallel axis theorem.
+///
+/// # Arguments
+///
+/// * `fixtures` - The fixtures of the body.
+///
+/// # Returns
+///
+/// A `MassData` structure containing the combined mass and moment of inertia.
+pub fn combined_mass_data(fixtures: &mut [Fixture]) -> MassData {
+    let mut parts = Vec::with_capacity(fixtures.len());
+    let mut mass = 0.0;
+    let mut center = Vector2::zeros();
+
+    for fixture in fixtures.iter_mut() {
+        let centroid = fixture.shape.centroid();
+        let mass_data = fixture.shape.calculate_mass_data(fixture.mat.density);
+
+        fixture.local.pos = fixture.local.to_world(&centroid);
+
+        mass += mass_data.mass;
+        center += mass_data.mass * fixture.local.pos.coords;
+        parts.push(mass_data);
+    }
+
+    if mass != 0.0 {
+        center /= mass;
+    }
+
+    let mut moment_inertia = 0.0;
+    for (fixture, mass_data) in fixtures.iter_mut().zip(&parts) {
+        fixture.local.pos -= center;
+        moment_inertia +=
+            mass_data.moment_inertia + mass_data.mass * fixture.local.pos.coords.norm_squared();
+    }
+
+    MassData::new(mass, moment_inertia)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use nalgebra::Point2;
+    use ordered_float::OrderedFloat;
+
+    use crate::circle::Circle;
+    use crate::polygon::Polygon;
+
+    fn rect(half_w: f64, half_h: f64) -> Shapes {
+        Shapes::Polygon(
+            Polygon::new(vec![
+                Point2::new(-half_w, -half_h),
+                Point2::new(half_w, -half_h),
+                Point2::new(half_w, half_h),
+                Point2::new(-half_w, half_h),
+            ])
+            .unwrap(),
+        )
+    }
+
+    fn material(density: f64) -> Material {
+        Material::new(
+            density,
+            OrderedFloat(0.0),
+            OrderedFloat(0.0),
+            OrderedFloat(0.0),
+        )
+    }
+
+    #[test]
+    fn test_two_halves_match_whole() {
+        let mut halves = vec![
+            Fixture::new(
+                rect(1.0, 1.0),
+                Transform::new(Point2::new(-1.0, 0.0)),
+                material(3.0),
+            ),
+            Fixture::new(
+                rect(1.0, 1.0),
+                Transform::new(Point2::new(1.0, 0.0)),
+                material(3.0),
+            ),
+        ];
+        let mut whole = vec![Fixture::new(
+            rect(2.0, 1.0),
+            Transform::default(),
+            material(3.0),
+        )];
+
+        let halves_data = combined_mass_data(&mut halves);
+        let whole_data = combined_mass_data(&mut whole);
+
+        assert!((halves_data.mass - whole_data.mass).abs() < 1e-9);
+        assert!((halves_data.moment_inertia - whole_data.moment_inertia).abs() < 1e-9);
+        assert_eq!(halves[0].local.pos, Point2::new(-1.0, 0.0));
+    }
+
+    #[test]
+    fn test_center_of_mass_moves_to_origin() {
+        // A light handle with a heavy head at one end
+        let mut hammer = vec![
+            Fixture::new(rect(0.25, 2.0), Transform::default(), material(1.0)),
+            Fixture::new(
+                Shapes::Circle(Circle {
+                    radius: OrderedFloat(1.0),
+                }),
+                Transform::new(Point2::new(0.0, -2.0)),
+                material(10.0),
+            ),
+        ];
+
+        let mass_data = combined_mass_data(&mut hammer);
+
+        let handle_mass = 2.0;
+        let head_mass = 10.0 * std::f64::consts::PI;
+        let center_y = -2.0 * head_mass / (handle_mass + head_mass);
+
+        assert!((mass_data.mass - (handle_mass + head_mass)).abs() < 1e-9);
+        assert!((hammer[0].local.pos.y + center_y).abs() < 1e-9);
+        assert!((hammer[1].local.pos.y - (-2.0 - center_y)).abs() < 1e-9);
+
+        let handle_inertia = handle_mass * (0.5 * 0.5 + 4.0 * 4.0) / 12.0;
+        let head_inertia = 0.5 * head_mass;
+        let expected = handle_inertia
+            + handle_mass * center_y * center_y
+            + head_inertia
+            + head_mass * (2.0 + center_y) * (2.0 + center_y);
+
+        assert!((mass_data.moment_inertia - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_offset_shapes_keep_their_place() {
+        let mut fixtures = vec![
+            Fixture::new(
+                Shapes::Circle(Circle {
+                    radius: OrderedFloat(1.0),
+                }),
+                Transform::default(),
+                material(4.0 / std::f64::consts::PI),
+            ),
+            // The square's centroid is at (3, 1), which the rotation and offset put at (0, 3)
+            Fixture::new(
+                Shapes::Polygon(
+                    Polygon::new(vec![
+                        Point2::new(2.0, 0.0),
+                        Point2::new(4.0, 0.0),
+                        Point2::new(4.0, 2.0),
+                        Point2::new(2.0, 2.0),
+                    ])
+                    .unwrap(),
+                ),
+                Transform::with_orientation(Point2::new(1.0, 0.0), std::f64::consts::FRAC_PI_2),
+                material(1.0),
+            ),
+        ];
+
+        combined_mass_data(&mut fixtures);
+
+        // Both fixtures weigh the same, so the center of mass is halfway between them
+        assert!((fixtures[0].local.pos - Point2::new(0.0, -1.5)).norm() < 1e-9);
+        assert!((fixtures[1].local.pos - Point2::new(0.0, 1.5)).norm() < 1e-9);
+        assert!((fixtures[1].local.orientation() - std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+    }
+}
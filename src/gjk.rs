@@ -0,0 +1,380 @@
+use nalgebra::{Point2, Vector2};
+
+use crate::constants::{GJK_MAX_ITERATIONS, GJK_TOLERANCE};
+use crate::custom_math::cross_v_v;
+use crate::shapes::ConvexPart;
+use crate::transform::Transform;
+
+// Adapted from Box2D's b2Distance and b2ShapeCast: https://github.com/erincatto/box2d
+/// A convex part placed in world space, as seen by GJK.
+///
+/// Rounded shapes are split into a core and a radius around it: a circle is a single point with
+/// its radius, while polygons and custom shapes are their own cores with no radius. GJK only
+/// works on the cores, which keeps it exact for circles, and the radii are accounted for
+/// afterwards.
+#[derive(Clone, Copy)]
+pub(crate) struct Proxy<'a> {
+    /// The convex part.
+    pub part: ConvexPart<'a>,
+    /// The transform that brings the part into world space.
+    pub tx: Transform,
+}
+
+impl Proxy<'_> {
+    /// Returns the point of the core furthest along a direction, in world space.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The world space direction, which does not need to be normalized.
+    pub fn core_support(&self, dir: &Vector2<f64>) -> Point2<f64> {
+        match self.part {
+            ConvexPart::Circle(_) => self.tx.pos,
+            ConvexPart::Polygon(p) => self
+                .tx
+                .to_world(&p.find_support(&self.tx.rotate_to_local(dir))),
+            ConvexPart::Custom(s) => s.support(&self.tx, dir),
+        }
+    }
+
+    /// Returns the radius around the core.
+    pub fn radius(&self) -> f64 {
+        match self.part {
+            ConvexPart::Circle(c) => *c.radius,
+            ConvexPart::Polygon(_) | ConvexPart::Custom(_) => 0.0,
+        }
+    }
+
+    /// Moves a point on the core out to the surface of the part along a direction.
+    ///
+    /// # Arguments
+    ///
+    /// * `core_point` - The point on the core, in world space.
+    /// * `dir` - The world space direction to move out in, which should be the direction from
+    ///   the core point towards whatever is being measured against.
+    pub fn surface_point(&self, core_point: &Point2<f64>, dir: &Vector2<f64>) -> Point2<f64> {
+        match self.part {
+            ConvexPart::Circle(c) => self
+                .tx
+                .to_world(&c.find_support(&self.tx.rotate_to_local(dir))),
+            ConvexPart::Polygon(_) | ConvexPart::Custom(_) => *core_point,
+        }
+    }
+}
+
+/// Finds the closest points between the cores of two parts using GJK.
+///
+/// # Arguments
+///
+/// * `a` - The first part.
+/// * `b` - The second part.
+///
+/// # Returns
+///
+/// The closest point on each core. If the cores overlap, both are the same point inside the
+/// overlap.
+pub(crate) fn closest_core_points(a: &Proxy, b: &Proxy) -> (Point2<f64>, Point2<f64>) {
+    let simplex = gjk(
+        |d| a.core_support(d),
+        |d| b.core_support(d),
+        &(b.tx.pos - a.tx.pos),
+    );
+
+    if simplex.count == 3 {
+        // The triangle contains the origin, so the cores overlap
+        let (p, _) = simplex.witness_points();
+        return (p, p);
+    }
+
+    simplex.witness_points()
+}
+
+/// Runs GJK on the Minkowski difference `b - a` of two convex sets given by their support
+/// functions.
+///
+/// # Arguments
+///
+/// * `support_a` - Returns the world space point of `a` furthest along a direction.
+/// * `support_b` - Returns the world space point of `b` furthest along a direction.
+/// * `start` - Any direction to pick the first support points with.
+///
+/// # Returns
+///
+/// The solved simplex. It is a triangle containing the origin if the sets overlap, and holds the
+/// closest feature to the origin otherwise.
+pub(crate) fn gjk<FA, FB>(support_a: FA, support_b: FB, start: &Vector2<f64>) -> Simplex
+where
+    FA: Fn(&Vector2<f64>) -> Point2<f64>,
+    FB: Fn(&Vector2<f64>) -> Point2<f64>,
+{
+    let mut simplex = Simplex::default();
+    simplex.push(SimplexVertex::new(support_a(start), support_b(&-start)));
+
+    for _ in 0..GJK_MAX_ITERATIONS {
+        // Remember the vertices so that cycling can be detected
+        let previous = simplex;
+
+        simplex.solve();
+        if simplex.count == 3 {
+            break;
+        }
+
+        let d = simplex.search_direction();
+        if d.norm_squared() < f64::EPSILON * f64::EPSILON {
+            // The origin lies on the simplex, so the sets touch
+            break;
+        }
+
+        // Look for a point of the Minkowski difference further towards the origin
+        let vertex = SimplexVertex::new(support_a(&-d), support_b(&d));
+        let repeated = previous.vertices[..previous.count]
+            .iter()
+            .any(|v| v.w_a == vertex.w_a && v.w_b == vertex.w_b);
+        let v = simplex.closest_point();
+        if repeated || v.norm_squared() - v.dot(&vertex.w) <= GJK_TOLERANCE * v.norm_squared() {
+            // No progress is possible, so the simplex is already closest
+            break;
+        }
+
+        simplex.push(vertex);
+    }
+
+    simplex
+}
+
+/// A vertex of the simplex, which is a point of the Minkowski difference of two cores.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct SimplexVertex {
+    /// The support point on the first core.
+    pub w_a: Point2<f64>,
+    /// The support point on the second core.
+    pub w_b: Point2<f64>,
+    /// The point of the Minkowski difference, `w_b - w_a`.
+    pub w: Vector2<f64>,
+    /// The barycentric weight of the vertex in the closest point.
+    pub a: f64,
+}
+
+impl SimplexVertex {
+    /// Creates a vertex from a support point on each core.
+    pub fn new(w_a: Point2<f64>, w_b: Point2<f64>) -> Self {
+        SimplexVertex {
+            w_a,
+            w_b,
+            w: w_b - w_a,
+            a: 1.0,
+        }
+    }
+}
+
+/// The simplex GJK grows towards the origin: a point, a segment or a triangle.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Simplex {
+    /// The vertices, of which only the first `count` are in use.
+    pub vertices: [SimplexVertex; 3],
+    /// The number of vertices in use.
+    pub count: usize,
+}
+
+impl Simplex {
+    /// Adds a vertex to the simplex.
+    pub fn push(&mut self, vertex: SimplexVertex) {
+        self.vertices[self.count] = vertex;
+        self.count += 1;
+    }
+
+    /// Returns the point of the simplex closest to the origin, after `solve`.
+    pub fn closest_point(&self) -> Vector2<f64> {
+        match self.count {
+            1 => self.vertices[0].w,
+            2 => self.vertices[0].a * self.vertices[0].w + self.vertices[1].a * self.vertices[1].w,
+            _ => Vector2::zeros(),
+        }
+    }
+
+    /// Returns the direction from the simplex towards the origin, after `solve`.
+    pub fn search_direction(&self) -> Vector2<f64> {
+        match self.count {
+            1 => -self.vertices[0].w,
+            2 => {
+                // Perpendicular to the segment, on the side of the origin
+                let e12 = self.vertices[1].w - self.vertices[0].w;
+                if cross_v_v(&e12, &-self.vertices[0].w) > 0.0 {
+                    Vector2::new(-e12.y, e12.x)
+                } else {
+                    Vector2::new(e12.y, -e12.x)
+                }
+            }
+            _ => Vector2::zeros(),
+        }
+    }
+
+    /// Returns the points on each core that make up the closest point, after `solve`.
+    pub fn witness_points(&self) -> (Point2<f64>, Point2<f64>) {
+        let vertices = &self.vertices[..self.count];
+        let p_a = vertices
+            .iter()
+            .fold(Vector2::zeros(), |acc, v| acc + v.a * v.w_a.coords);
+        let p_b = vertices
+            .iter()
+            .fold(Vector2::zeros(), |acc, v| acc + v.a * v.w_b.coords);
+
+        (Point2::from(p_a), Point2::from(p_b))
+    }
+
+    /// Reduces the simplex to the smallest feature containing the point closest to the origin,
+    /// and sets the barycentric weights of its vertices.
+    ///
+    /// A triangle is only kept if it contains the origin.
+    pub fn solve(&mut self) {
+        match self.count {
+            2 => self.solve2(),
+            3 => self.solve3(),
+            _ => {}
+        }
+    }
+
+    /// Solves a segment using barycentric coordinates.
+    fn solve2(&mut self) {
+        let w1 = self.vertices[0].w;
+        let w2 = self.vertices[1].w;
+        let e12 = w2 - w1;
+
+        // Closest to the first vertex
+        let d12_2 = -w1.dot(&e12);
+        if d12_2 <= 0.0 {
+            self.vertices[0].a = 1.0;
+            self.count = 1;
+            return;
+        }
+
+        // Closest to the second vertex
+        let d12_1 = w2.dot(&e12);
+        if d12_1 <= 0.0 {
+            self.vertices[1].a = 1.0;
+            self.vertices[0] = self.vertices[1];
+            self.count = 1;
+            return;
+        }
+
+        self.set_edge(0, 1, d12_1, d12_2);
+    }
+
+    /// Solves a triangle by testing its vertex, edge and interior regions.
+    fn solve3(&mut self) {
+        let w1 = self.vertices[0].w;
+        let w2 = self.vertices[1].w;
+        let w3 = self.vertices[2].w;
+
+        let e12 = w2 - w1;
+        let d12_1 = w2.dot(&e12);
+        let d12_2 = -w1.dot(&e12);
+
+        let e13 = w3 - w1;
+        let d13_1 = w3.dot(&e13);
+        let d13_2 = -w1.dot(&e13);
+
+        let e23 = w3 - w2;
+        let d23_1 = w3.dot(&e23);
+        let d23_2 = -w2.dot(&e23);
+
+        let n123 = cross_v_v(&e12, &e13);
+        let d123_1 = n123 * cross_v_v(&w2, &w3);
+        let d123_2 = n123 * cross_v_v(&w3, &w1);
+        let d123_3 = n123 * cross_v_v(&w1, &w2);
+
+        if d12_2 <= 0.0 && d13_2 <= 0.0 {
+            self.set_vertex(0);
+        } else if d12_1 > 0.0 && d12_2 > 0.0 && d123_3 <= 0.0 {
+            self.set_edge(0, 1, d12_1, d12_2);
+        } else if d13_1 > 0.0 && d13_2 > 0.0 && d123_2 <= 0.0 {
+            self.set_edge(0, 2, d13_1, d13_2);
+        } else if d12_1 <= 0.0 && d23_2 <= 0.0 {
+            self.set_vertex(1);
+        } else if d13_1 <= 0.0 && d23_1 <= 0.0 {
+            self.set_vertex(2);
+        } else if d23_1 > 0.0 && d23_2 > 0.0 && d123_1 <= 0.0 {
+            self.set_edge(1, 2, d23_1, d23_2);
+        } else {
+            // The origin is inside the triangle
+            let inv = 1.0 / (d123_1 + d123_2 + d123_3);
+            self.vertices[0].a = d123_1 * inv;
+            self.vertices[1].a = d123_2 * inv;
+            self.vertices[2].a = d123_3 * inv;
+        }
+    }
+
+    /// Reduces the simplex to one of its vertices.
+    fn set_vertex(&mut self, i: usize) {
+        self.vertices[0] = self.vertices[i];
+        self.vertices[0].a = 1.0;
+        self.count = 1;
+    }
+
+    /// Reduces the simplex to one of its edges, weighting its vertices by the given unnormalized
+    /// barycentric coordinates.
+    fn set_edge(&mut self, i: usize, j: usize, d_i: f64, d_j: f64) {
+        let inv = 1.0 / (d_i + d_j);
+        let (v_i, v_j) = (self.vertices[i], self.vertices[j]);
+
+        self.vertices[0] = SimplexVertex {
+            a: d_i * inv,
+            ..v_i
+        };
+        self.vertices[1] = SimplexVertex {
+            a: d_j * inv,
+            ..v_j
+        };
+        self.count = 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_segment() {
+        let mut simplex = Simplex::default();
+        simplex.push(SimplexVertex::new(Point2::origin(), Point2::new(-1.0, 1.0)));
+        simplex.push(SimplexVertex::new(Point2::origin(), Point2::new(1.0, 1.0)));
+        simplex.solve();
+
+        assert_eq!(simplex.count, 2);
+        assert!((simplex.closest_point() - Vector2::new(0.0, 1.0)).norm() < 1e-12);
+
+        // The origin is closest to the first vertex, so the second is dropped
+        let mut simplex = Simplex::default();
+        simplex.push(SimplexVertex::new(Point2::origin(), Point2::new(1.0, 1.0)));
+        simplex.push(SimplexVertex::new(Point2::origin(), Point2::new(2.0, 3.0)));
+        simplex.solve();
+
+        assert_eq!(simplex.count, 1);
+        assert_eq!(simplex.closest_point(), Vector2::new(1.0, 1.0));
+    }
+
+    #[test]
+    fn test_solve_triangle() {
+        let points = [(-1.0, -1.0), (1.0, -1.0), (0.0, 1.0)];
+
+        // Containing the origin
+        let mut simplex = Simplex::default();
+        for (x, y) in points {
+            simplex.push(SimplexVertex::new(Point2::origin(), Point2::new(x, y)));
+        }
+        simplex.solve();
+        assert_eq!(simplex.count, 3);
+        assert_eq!(simplex.closest_point(), Vector2::zeros());
+
+        // Shifted up so that the bottom edge is closest
+        let mut simplex = Simplex::default();
+        for (x, y) in points {
+            simplex.push(SimplexVertex::new(
+                Point2::origin(),
+                Point2::new(x, y + 2.0),
+            ));
+        }
+        simplex.solve();
+        assert_eq!(simplex.count, 2);
+        assert!((simplex.closest_point() - Vector2::new(0.0, 1.0)).norm() < 1e-12);
+    }
+}
@@ -0,0 +1,53 @@
+use crate::distance_joint::DistanceJoint;
+use crate::object::Object;
+use crate::scene::BodyHandle;
+
+/// A constraint between two bodies, solved by `Scene::step` alongside the contacts.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Joint {
+    /// Keeps two anchor points at a distance.
+    Distance(DistanceJoint),
+}
+
+impl From<DistanceJoint> for Joint {
+    fn from(joint: DistanceJoint) -> Self {
+        Joint::Distance(joint)
+    }
+}
+
+impl Joint {
+    /// Returns the handles of the two bodies the joint connects.
+    pub fn bodies(&self) -> (BodyHandle, BodyHandle) {
+        match self {
+            Joint::Distance(joint) => (joint.a(), joint.b()),
+        }
+    }
+
+    /// Returns `true` if the two bodies still collide with each other.
+    pub fn collide_connected(&self) -> bool {
+        match self {
+            Joint::Distance(joint) => joint.collide_connected,
+        }
+    }
+
+    /// Prepares the joint for the solver from the bodies' positions at the start of the step.
+    pub fn initialize(&mut self, a: &Object, b: &Object, dt: f64) {
+        match self {
+            Joint::Distance(joint) => joint.initialize(a, b, dt),
+        }
+    }
+
+    /// Applies an impulse so that the bodies' velocities respect the joint.
+    pub fn apply_impulse(&mut self, a: &mut Object, b: &mut Object, dt: f64) {
+        match self {
+            Joint::Distance(joint) => joint.apply_impulse(a, b, dt),
+        }
+    }
+
+    /// Moves the bodies back to where the joint allows, after their velocities are integrated.
+    pub fn positional_correction(&mut self, a: &mut Object, b: &mut Object) {
+        match self {
+            Joint::Distance(joint) => joint.positional_correction(a, b),
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use nalgebra::Vector2;
+use rand::Rng;
 
 use crate::types::{MeterPerSec, NewtonMeter, RadianPerSec};
 
@@ -33,4 +34,29 @@ impl Kinematics {
             torque,
         }
     }
+
+    /// Creates kinematics with random values drawn from the given random number generator.
+    ///
+    /// Each velocity component is drawn from `[-10, 10)`, and the angular velocity and torque from
+    /// `[-1, 1)`. Passing a seeded generator makes the result reproducible.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator to draw from.
+    ///
+    /// # Returns
+    ///
+    /// A new `Kinematics` instance with random values.
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let vel = Vector2::new(rng.gen_range(-10.0..10.0), rng.gen_range(-10.0..10.0));
+
+        Kinematics::new(vel, rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0))
+    }
+}
+
+impl Default for Kinematics {
+    /// Returns kinematics at rest with no torque.
+    fn default() -> Self {
+        Kinematics::new(Vector2::zeros(), 0.0, 0.0)
+    }
 }
@@ -0,0 +1,44 @@
+//! A simple 2D rigid body physics engine.
+//!
+//! The simulation itself has no rendering dependencies. Enable the `render-piston` feature to get
+//! the `draw`/`render` methods backed by Piston and the sandbox binary.
+
+pub mod aabb;
+pub mod arena;
+pub mod broad_phase;
+pub mod brute_force;
+pub mod circle;
+pub mod collision;
+pub mod collision_filter;
+pub mod concave_polygon;
+pub mod constants;
+pub mod contact_event;
+pub mod custom_math;
+pub mod distance;
+pub mod distance_joint;
+pub mod dynamic_tree;
+pub mod epa;
+pub mod fixture;
+pub mod gjk;
+pub mod joint;
+pub mod kinematics;
+pub mod manifold;
+pub mod mass_data;
+pub mod material;
+pub mod object;
+pub mod object_builder;
+pub mod polygon;
+pub mod pre_solve_contact;
+pub mod ray;
+pub mod scene;
+pub mod sensor;
+pub mod shape_cast;
+pub mod shape_error;
+pub mod shapes;
+pub mod spatial_hash_grid;
+pub mod support_map;
+pub mod sweep_and_prune;
+pub mod transform;
+pub mod tree_broad_phase;
+pub mod types;
+pub mod world_settings;
@@ -11,35 +11,21 @@ use piston::window::WindowSettings;
 
 use nalgebra::{Point2, Vector2};
 use ordered_float::OrderedFloat;
-use std::cell::RefCell;
-use std::f64::INFINITY;
-use std::rc::Rc;
-
-mod circle;
-mod collision;
-mod constants;
-mod custom_math;
-mod kinematics;
-mod manifold;
-mod mass_data;
-mod material;
-mod object;
-mod polygon;
-mod scene;
-mod shapes;
-mod transform;
-mod types;
-
-use circle::Circle;
-use constants::BLACK;
-use kinematics::Kinematics;
-use mass_data::MassData;
-use material::Material;
-use object::Object;
-use polygon::Polygon;
-use scene::Scene;
-use shapes::Shapes;
-use transform::Transform;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use physics_2d::circle::Circle;
+use physics_2d::concave_polygon::ConcavePolygon;
+use physics_2d::constants::BLACK;
+use physics_2d::fixture::Fixture;
+use physics_2d::kinematics::Kinematics;
+use physics_2d::material::Material;
+use physics_2d::object::{BodyType, Object};
+use physics_2d::polygon::Polygon;
+use physics_2d::scene::Scene;
+use physics_2d::shape_error::ShapeError;
+use physics_2d::shapes::Shapes;
+use physics_2d::transform::Transform;
 
 /// Represents the application's main structure, including the OpenGL backend and scene.
 pub struct App {
@@ -76,90 +62,95 @@ impl App {
     }
 }
 
-/// Creates test objects for the scene and returns them as a vector of `Object` references.
-fn create_test_objects() -> Vec<Rc<RefCell<Object>>> {
+/// Creates test objects for the scene and returns them as a vector of `Object`s.
+///
+/// The dynamic objects get random materials drawn from `rng`.
+fn create_test_objects(rng: &mut StdRng) -> Result<Vec<Object>, ShapeError> {
     // Create a circle
-    let circle = Object::new(
-        Shapes::Circle(Circle {
-            radius: OrderedFloat(50.0),
-        }),
-        Transform::new(Point2::new(100.0, 100.0)),
-        None,
-        None,
-        Some(Kinematics::new(Vector2::new(30.0, 0.0), 0.0, 0.0)),
-    );
+    let circle = Object::builder(Shapes::Circle(Circle::new(50.0)?))
+        .transform(Transform::new(Point2::new(100.0, 100.0)))
+        .kinematics(Kinematics::new(Vector2::new(30.0, 0.0), 0.0, 0.0))
+        .random_material(rng)
+        .build();
 
     // Create a circle
-    let circle2 = Object::new(
-        Shapes::Circle(Circle {
-            radius: OrderedFloat(25.0),
-        }),
-        Transform::new(Point2::new(100.0, 300.0)),
-        None,
-        None,
-        Some(Kinematics::new(Vector2::new(30.0, -50.0), 0.0, 0.0)),
-    );
+    let circle2 = Object::builder(Shapes::Circle(Circle::new(25.0)?))
+        .transform(Transform::new(Point2::new(100.0, 300.0)))
+        .kinematics(Kinematics::new(Vector2::new(30.0, -50.0), 0.0, 0.0))
+        .random_material(rng)
+        .build();
 
     // Create a convex polygon (triangle)
-    let convex_polygon = Object::new(
-        Shapes::Polygon(Polygon::new(
-            vec![
-                Point2::new(200.0, 200.0),
-                Point2::new(250.0, 300.0),
-                Point2::new(300.0, 200.0),
-            ],
-            None,
-        )),
-        Transform::new(Point2::new(400.0, 200.0)),
-        None,
-        None,
-        Some(Kinematics::new(Vector2::new(-20.0, -20.0), 0.0, 0.0)),
-    );
+    let convex_polygon = Object::builder(Shapes::Polygon(Polygon::new(vec![
+        Point2::new(200.0, 200.0),
+        Point2::new(250.0, 300.0),
+        Point2::new(300.0, 200.0),
+    ])?))
+    .transform(Transform::new(Point2::new(400.0, 200.0)))
+    .kinematics(Kinematics::new(Vector2::new(-20.0, -20.0), 0.0, 0.0))
+    .random_material(rng)
+    .build();
 
     // Create a concave polygon
-    let concave_polygon = Object::new(
-        Shapes::Polygon(Polygon::new(
-            vec![
-                Point2::new(350.0, 200.0),
-                Point2::new(350.0, 300.0),
-                Point2::new(500.0, 400.0),
-                Point2::new(400.0, 300.0),
-                Point2::new(500.0, 200.0),
-            ],
-            None,
-        )),
-        Transform::new(Point2::new(600.0, 300.0)),
-        None,
-        None,
-        Some(Kinematics::new(Vector2::new(-30.0, -30.0), 0.0, 0.0)),
-    );
+    let concave_polygon = Object::builder(Shapes::ConcavePolygon(ConcavePolygon::new(vec![
+        Point2::new(350.0, 200.0),
+        Point2::new(350.0, 300.0),
+        Point2::new(500.0, 400.0),
+        Point2::new(400.0, 300.0),
+        Point2::new(500.0, 200.0),
+    ])?))
+    .transform(Transform::new(Point2::new(600.0, 300.0)))
+    .kinematics(Kinematics::new(Vector2::new(-30.0, -30.0), 0.0, 0.0))
+    .random_material(rng)
+    .build();
+
+    // Create a hammer from a handle and a head
+    let hammer = Object::builder(Shapes::Polygon(Polygon::new(vec![
+        Point2::new(-5.0, -40.0),
+        Point2::new(5.0, -40.0),
+        Point2::new(5.0, 40.0),
+        Point2::new(-5.0, 40.0),
+    ])?))
+    .fixture(Fixture::new(
+        Shapes::Polygon(Polygon::new(vec![
+            Point2::new(-25.0, -10.0),
+            Point2::new(25.0, -10.0),
+            Point2::new(25.0, 10.0),
+            Point2::new(-25.0, 10.0),
+        ])?),
+        Transform::new(Point2::new(0.0, -50.0)),
+        Material::random(rng),
+    ))
+    .transform(Transform::with_orientation(Point2::new(250.0, 100.0), 0.3))
+    .random_material(rng)
+    .build();
 
     // Create a floor
-    let floor = Object::new(
-        Shapes::Polygon(Polygon::new(
-            vec![
-                Point2::new(10.0, 580.0),
-                Point2::new(790.0, 580.0),
-                Point2::new(790.0, 590.0),
-                Point2::new(10.0, 590.0),
-            ],
-            None,
-        )),
-        Transform::new(Point2::new(0.0, 0.0)),
-        Some(Material::new(
-            INFINITY,
-            OrderedFloat(1.0),
-            OrderedFloat(1.0),
-            OrderedFloat(1.0),
-        )),
-        Some(MassData::new(INFINITY, INFINITY)),
-        Some(Kinematics::new(Vector2::zeros(), 0.0, 0.0)),
-    );
-
-    [circle, circle2, convex_polygon, concave_polygon, floor]
-        .into_iter()
-        .map(|s| Rc::new(RefCell::new(s)))
-        .collect()
+    let floor = Object::builder(Shapes::Polygon(Polygon::new(vec![
+        Point2::new(-390.0, -5.0),
+        Point2::new(390.0, -5.0),
+        Point2::new(390.0, 5.0),
+        Point2::new(-390.0, 5.0),
+    ])?))
+    .transform(Transform::new(Point2::new(400.0, 585.0)))
+    .material(Material::new(
+        1.0,
+        OrderedFloat(1.0),
+        OrderedFloat(1.0),
+        OrderedFloat(1.0),
+    ))
+    .kinematics(Kinematics::new(Vector2::zeros(), 0.0, 0.0))
+    .body_type(BodyType::Static)
+    .build();
+
+    Ok(vec![
+        circle,
+        circle2,
+        convex_polygon,
+        concave_polygon,
+        hammer,
+        floor,
+    ])
 }
 
 /// The main function responsible for creating the window, initializing the application,
@@ -175,13 +166,17 @@ fn main() {
         .build()
         .unwrap();
 
+    let mut scene = Scene::new();
+    // Change the seed to try out other materials
+    let mut rng = StdRng::seed_from_u64(0);
+    for object in create_test_objects(&mut rng).expect("test objects should be valid shapes") {
+        scene.add_body(object);
+    }
+
     // Create a new game and run it.
     let mut app = App {
         gl: GlGraphics::new(opengl),
-        scene: Scene {
-            objects: create_test_objects(),
-            contacts: Vec::new(),
-        },
+        scene,
     };
 
     let mut events = Events::new(EventSettings::new());
@@ -1,23 +1,26 @@
 use nalgebra::Vector2;
 use ordered_float::OrderedFloat;
-use std::cell::RefCell;
 use std::cmp::{max, min};
-use std::f64::EPSILON;
-use std::rc::Rc;
 
-use crate::collision::{circle_circle, circle_polygon, polygon_polygon};
-use crate::constants::{GRAVITY, PEN_ALLOWANCE, PERCENT_CORRECTION};
+use crate::collision::{circle_circle, circle_polygon, convex_convex, polygon_polygon};
 use crate::custom_math::{cross_s_v, cross_v_v};
+use crate::fixture::PartId;
 use crate::object::Object;
-use crate::shapes::{Shape, ShapeDiscriminant};
+use crate::scene::BodyHandle;
+use crate::shapes::ConvexPart;
 use crate::types::{Meter, NormalizedCoefficient};
+use crate::world_settings::WorldSettings;
 
 /// Represents a collision manifold between two objects.
 pub struct Manifold {
-    /// The first object involved in the collision.
-    pub a: Rc<RefCell<Object>>,
-    /// The second object involved in the collision.
-    pub b: Rc<RefCell<Object>>,
+    /// The handle of the first object involved in the collision.
+    pub a: BodyHandle,
+    /// The handle of the second object involved in the collision.
+    pub b: BodyHandle,
+    /// The convex part of the first object that is colliding.
+    pub part_a: PartId,
+    /// The convex part of the second object that is colliding.
+    pub part_b: PartId,
     /// The penetration depth of the collision.
     pub penetration: Meter,
     /// The collision normal pointing from object A to object B.
@@ -26,8 +29,27 @@ pub struct Manifold {
     pub contacts: [Vector2<f64>; 2],
     /// Number of valid contact points.
     pub contact_count: usize,
+    /// The total impulse applied along the normal at each contact point, once the manifold has
+    /// been solved.
+    pub normal_impulses: [f64; 2],
+    /// The total friction impulse applied at each contact point, along the normal turned a
+    /// quarter clockwise, once the manifold has been solved.
+    pub tangent_impulses: [f64; 2],
+    /// Whether one of the parts is a sensor, in which case the manifold only records the
+    /// overlap and produces no collision response.
+    pub is_sensor: bool,
+    /// Whether the solver responds to the contact this step. A pre-solve hook may clear it to
+    /// let the bodies pass through each other, as with one-way platforms.
+    pub enabled: bool,
+    /// The speed at which the surface of `a` moves under `b`, along the normal turned a quarter
+    /// clockwise. Friction drags `b` towards this speed, as with conveyor belts.
+    pub tangent_speed: f64,
+    /// The factor every impulse of this manifold is multiplied by.
+    pub impulse_scale: f64,
     /// Coefficient of restitution for the collision.
     mixed_restitution: NormalizedCoefficient,
+    /// Whether the restitution was set explicitly, which keeps it for slow contacts too.
+    restitution_overridden: bool,
     /// Coefficient of dynamic friction for the collision.
     mixed_dynamic_friction: NormalizedCoefficient,
     /// Coefficient of static friction for the collision.
@@ -39,86 +61,181 @@ impl Manifold {
     ///
     /// # Arguments
     ///
-    /// * `a` - The first object involved in the collision.
-    /// * `b` - The second object involved in the collision.
+    /// * `a` - The handle of the first object involved in the collision.
+    /// * `b` - The handle of the second object involved in the collision.
     ///
     /// # Returns
     ///
     /// A new `Manifold` instance.
-    pub fn new(a: Rc<RefCell<Object>>, b: Rc<RefCell<Object>>) -> Manifold {
+    pub fn new(a: BodyHandle, b: BodyHandle) -> Manifold {
+        Self::with_parts(a, b, PartId::default(), PartId::default())
+    }
+
+    /// Creates a new `Manifold` instance representing a collision between convex parts of two
+    /// objects.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The handle of the first object involved in the collision.
+    /// * `b` - The handle of the second object involved in the collision.
+    /// * `part_a` - The convex part of the first object.
+    /// * `part_b` - The convex part of the second object.
+    ///
+    /// # Returns
+    ///
+    /// A new `Manifold` instance.
+    pub fn with_parts(a: BodyHandle, b: BodyHandle, part_a: PartId, part_b: PartId) -> Manifold {
         Manifold {
             a,
             b,
+            part_a,
+            part_b,
             penetration: OrderedFloat(0.0),
             normal: Vector2::zeros(),
             contacts: [Vector2::zeros(); 2],
             contact_count: 0,
+            normal_impulses: [0.0; 2],
+            tangent_impulses: [0.0; 2],
+            is_sensor: false,
+            enabled: true,
+            tangent_speed: 0.0,
+            impulse_scale: 1.0,
             mixed_restitution: OrderedFloat(0.0),
+            restitution_overridden: false,
             mixed_dynamic_friction: OrderedFloat(0.0),
             mixed_static_friction: OrderedFloat(0.0),
         }
     }
 
-    /// Dispatches collision detection to the appropriate function based on the shape of a and b.
-    pub fn solve(&mut self) {
-        let a = self.a.borrow().shape.discriminant();
-        let b = self.b.borrow().shape.discriminant();
-
-        match (a, b) {
-            (ShapeDiscriminant::Circle, ShapeDiscriminant::Circle) => circle_circle(self),
-            (ShapeDiscriminant::Polygon, ShapeDiscriminant::Polygon) => polygon_polygon(self),
-            (ShapeDiscriminant::Circle, ShapeDiscriminant::Polygon) => circle_polygon(self, true),
-            _ => circle_polygon(self, false),
+    /// Dispatches collision detection to the appropriate function based on the shapes of the
+    /// colliding parts of a and b.
+    ///
+    /// Pairs of built-in shapes have their own routines, while anything involving a custom shape
+    /// falls back on the generic GJK/EPA path.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The object referred to by `self.a`.
+    /// * `b` - The object referred to by `self.b`.
+    pub fn solve(&mut self, a: &Object, b: &Object) {
+        match (a.part(self.part_a).0, b.part(self.part_b).0) {
+            (ConvexPart::Circle(_), ConvexPart::Circle(_)) => circle_circle(self, a, b),
+            (ConvexPart::Polygon(_), ConvexPart::Polygon(_)) => polygon_polygon(self, a, b),
+            (ConvexPart::Circle(_), ConvexPart::Polygon(_)) => circle_polygon(self, a, b, true),
+            (ConvexPart::Polygon(_), ConvexPart::Circle(_)) => circle_polygon(self, a, b, false),
+            (ConvexPart::Custom(_), _) | (_, ConvexPart::Custom(_)) => convex_convex(self, a, b),
         };
     }
 
-    /// Initializes the manifold properties based on the object materials and contact points.
+    /// Combines the materials of the two parts into the coefficients used by the solver.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The object referred to by `self.a`.
+    /// * `b` - The object referred to by `self.b`.
+    pub fn mix_materials(&mut self, a: &Object, b: &Object) {
+        let mat_a = &a.fixtures[self.part_a.fixture].mat;
+        let mat_b = &b.fixtures[self.part_b.fixture].mat;
+
+        self.mixed_restitution = min(mat_a.restitution, mat_b.restitution);
+        self.restitution_overridden = false;
+        self.mixed_dynamic_friction =
+            OrderedFloat((mat_a.dynamic_friction * mat_b.dynamic_friction).sqrt());
+        self.mixed_static_friction =
+            OrderedFloat((mat_a.static_friction * mat_b.static_friction).sqrt());
+    }
+
+    /// Returns the coefficient of restitution used for the contact.
+    pub fn restitution(&self) -> NormalizedCoefficient {
+        self.mixed_restitution
+    }
+
+    /// Overrides the coefficient of restitution used for the contact this step.
+    ///
+    /// Unlike the mixed restitution, an override also applies to contacts too slow to bounce
+    /// otherwise.
+    pub fn set_restitution(&mut self, restitution: NormalizedCoefficient) {
+        self.mixed_restitution = restitution;
+        self.restitution_overridden = true;
+    }
+
+    /// Returns the coefficient of static friction used for the contact.
+    pub fn static_friction(&self) -> NormalizedCoefficient {
+        self.mixed_static_friction
+    }
+
+    /// Overrides the coefficient of static friction used for the contact this step.
+    pub fn set_static_friction(&mut self, friction: NormalizedCoefficient) {
+        self.mixed_static_friction = friction;
+    }
+
+    /// Returns the coefficient of dynamic friction used for the contact.
+    pub fn dynamic_friction(&self) -> NormalizedCoefficient {
+        self.mixed_dynamic_friction
+    }
+
+    /// Overrides the coefficient of dynamic friction used for the contact this step.
+    pub fn set_dynamic_friction(&mut self, friction: NormalizedCoefficient) {
+        self.mixed_dynamic_friction = friction;
+    }
+
+    /// Returns `true` if the solver should respond to the manifold.
+    fn is_active(&self) -> bool {
+        self.enabled && !self.is_sensor
+    }
+
+    /// Prepares the manifold for the solver, dropping the restitution of contacts too slow to
+    /// bounce unless it was overridden with `set_restitution`.
+    ///
+    /// Does nothing for a sensor or disabled manifold.
     ///
     /// # Arguments
     ///
+    /// * `a` - The object referred to by `self.a`.
+    /// * `b` - The object referred to by `self.b`.
+    /// * `settings` - The settings of the scene being stepped.
     /// * `dt` - The time step for the simulation.
-    pub fn initialize(&mut self, dt: f64) {
-        self.mixed_restitution = min(
-            self.a.borrow().mat.restitution,
-            self.b.borrow().mat.restitution,
-        );
-        self.mixed_dynamic_friction = OrderedFloat(
-            (self.a.borrow().mat.dynamic_friction * self.b.borrow().mat.dynamic_friction).sqrt(),
-        );
-        self.mixed_static_friction = OrderedFloat(
-            (self.a.borrow().mat.static_friction * self.b.borrow().mat.static_friction).sqrt(),
-        );
+    pub fn initialize(&mut self, a: &Object, b: &Object, settings: &WorldSettings, dt: f64) {
+        if !self.is_active() || self.restitution_overridden {
+            return;
+        }
+
+        let rest_speed = settings.rest_speed(dt);
 
         for i in 0..self.contact_count {
-            let a_radii = self.contacts[i] - self.a.borrow().tx.pos.coords;
-            let b_radii = self.contacts[i] - self.b.borrow().tx.pos.coords;
-            let rel_vel = self.b.borrow().kinematics.vel
-                + cross_s_v(self.b.borrow().kinematics.angular_vel, &b_radii)
-                - self.a.borrow().kinematics.vel
-                - cross_s_v(self.a.borrow().kinematics.angular_vel, &a_radii);
-
-            if rel_vel.norm_squared() < (dt * GRAVITY).norm_squared() + EPSILON {
+            let a_radii = self.contacts[i] - a.tx.pos.coords;
+            let b_radii = self.contacts[i] - b.tx.pos.coords;
+            let rel_vel = b.kinematics.vel + cross_s_v(b.kinematics.angular_vel, &b_radii)
+                - a.kinematics.vel
+                - cross_s_v(a.kinematics.angular_vel, &a_radii);
+
+            if rel_vel.norm_squared() < rest_speed * rest_speed + f64::EPSILON {
                 self.mixed_restitution = OrderedFloat(0.0);
             }
         }
     }
 
     /// Applies impulse to resolve the collision.
-    pub fn apply_impulse(&mut self) {
-        if self.a.borrow().mass_data.mass.is_infinite()
-            && self.b.borrow().mass_data.mass.is_infinite()
-        {
-            self.infinite_mass_correction();
+    ///
+    /// Does nothing for a sensor or disabled manifold.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The object referred to by `self.a`.
+    /// * `b` - The object referred to by `self.b`.
+    pub fn apply_impulse(&mut self, a: &mut Object, b: &mut Object) {
+        if !self.is_active() {
             return;
         }
 
+        let tangent = Vector2::new(self.normal.y, -self.normal.x);
+
         for i in 0..self.contact_count {
-            let ra = self.contacts[i] - self.a.borrow().tx.pos.coords;
-            let rb = self.contacts[i] - self.b.borrow().tx.pos.coords;
-            let rv = self.b.borrow().kinematics.vel
-                + cross_s_v(self.b.borrow().kinematics.angular_vel, &rb)
-                - self.a.borrow().kinematics.vel
-                - cross_s_v(self.a.borrow().kinematics.angular_vel, &ra);
+            let ra = self.contacts[i] - a.tx.pos.coords;
+            let rb = self.contacts[i] - b.tx.pos.coords;
+            let rv = b.kinematics.vel + cross_s_v(b.kinematics.angular_vel, &rb)
+                - a.kinematics.vel
+                - cross_s_v(a.kinematics.angular_vel, &ra);
             let contact_vel = rv.dot(&self.normal);
 
             if contact_vel > 0.0 {
@@ -127,56 +244,90 @@ impl Manifold {
 
             let ra_cross_n = cross_v_v(&ra, &self.normal);
             let rb_cross_n = cross_v_v(&rb, &self.normal);
-            let inv_mass_sum = self.a.borrow().mass_data.inv_mass
-                + self.b.borrow().mass_data.inv_mass
-                + (ra_cross_n * ra_cross_n) * self.a.borrow().mass_data.inv_m_inertia
-                + (rb_cross_n * rb_cross_n) * self.b.borrow().mass_data.inv_m_inertia;
+            let inv_mass_sum = a.inv_mass()
+                + b.inv_mass()
+                + (ra_cross_n * ra_cross_n) * a.inv_m_inertia()
+                + (rb_cross_n * rb_cross_n) * b.inv_m_inertia();
             let mut imp_s = -(1.0 + *self.mixed_restitution) * contact_vel;
 
             imp_s /= inv_mass_sum;
             imp_s /= self.contact_count as f64;
+            imp_s *= self.impulse_scale;
 
             let imp = self.normal * imp_s;
-            self.a.borrow_mut().apply_impulse(&-imp, &ra);
-            self.b.borrow_mut().apply_impulse(&imp, &rb);
+            a.apply_impulse(&-imp, &ra);
+            b.apply_impulse(&imp, &rb);
+            self.normal_impulses[i] += imp_s;
 
-            // Fiction resolution
-            // TODO
-            // rv = self.b.borrow().kinematics.vel + cross_s_v(self.b.borrow().kinematics.angular_vel, &rb) - self.a.borrow().kinematics.vel - cross_s_v(self.a.borrow().kinematics.angular_vel, &ra);
+            // Friction resolution
+            let rv = b.kinematics.vel + cross_s_v(b.kinematics.angular_vel, &rb)
+                - a.kinematics.vel
+                - cross_s_v(a.kinematics.angular_vel, &ra);
+            let slip = rv - self.normal * rv.dot(&self.normal) - tangent * self.tangent_speed;
 
-            // let t = (rv - (self.normal * rv.dot(&self.normal))).normalize();
-            // let mut tan_mag = -rv.dot(&t);
+            if slip.norm_squared() <= f64::EPSILON {
+                continue;
+            }
+
+            let t = slip.normalize();
+            let ra_cross_t = cross_v_v(&ra, &t);
+            let rb_cross_t = cross_v_v(&rb, &t);
+            let inv_mass_sum_t = a.inv_mass()
+                + b.inv_mass()
+                + (ra_cross_t * ra_cross_t) * a.inv_m_inertia()
+                + (rb_cross_t * rb_cross_t) * b.inv_m_inertia();
+            let mut tan_mag = -slip.dot(&t);
 
-            // tan_mag /= inv_mass_sum;
-            // tan_mag /= self.contact_count as f64;
+            tan_mag /= inv_mass_sum_t;
+            tan_mag /= self.contact_count as f64;
+            tan_mag *= self.impulse_scale;
 
-            // if equal(tan_mag, 0.0) { return; }
+            if tan_mag.abs() <= f64::EPSILON {
+                continue;
+            }
 
-            // let tan_imp = if tan_mag.abs() < imp_s * *self.mixed_static_friction { t * tan_mag }
-            // else { t * -imp_s * *self.mixed_dynamic_friction };
+            // Coulomb's law: stick while the tangential impulse is inside the static cone,
+            // otherwise slide with the dynamic coefficient
+            let tan_imp = if tan_mag.abs() < imp_s * *self.mixed_static_friction {
+                t * tan_mag
+            } else {
+                t * -imp_s * *self.mixed_dynamic_friction
+            };
 
-            // self.a.borrow_mut().apply_impulse(&-tan_imp, &ra);
-            // self.b.borrow_mut().apply_impulse(&tan_imp, &rb);
+            a.apply_impulse(&-tan_imp, &ra);
+            b.apply_impulse(&tan_imp, &rb);
+            self.tangent_impulses[i] += tan_imp.dot(&tangent);
         }
     }
 
     /// Keeps objects from intersecting
-    pub fn positional_correction(&mut self) {
-        let correction = *(max(self.penetration - PEN_ALLOWANCE, OrderedFloat(0.0))
-            / (self.a.borrow().mass_data.inv_mass + self.b.borrow().mass_data.inv_mass))
-            * self.normal
-            * *PERCENT_CORRECTION;
-        let a_inv_mass = self.a.borrow().mass_data.inv_mass;
-        let b_inv_mass = self.b.borrow().mass_data.inv_mass;
+    ///
+    /// Does nothing for a sensor or disabled manifold.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The object referred to by `self.a`.
+    /// * `b` - The object referred to by `self.b`.
+    /// * `settings` - The settings of the scene being stepped.
+    pub fn positional_correction(
+        &mut self,
+        a: &mut Object,
+        b: &mut Object,
+        settings: &WorldSettings,
+    ) {
+        if !self.is_active() {
+            return;
+        }
 
-        self.a.borrow_mut().tx.pos -= correction * a_inv_mass;
-        self.b.borrow_mut().tx.pos += correction * b_inv_mass;
-    }
+        let correction = *(max(self.penetration - settings.pen_allowance, OrderedFloat(0.0))
+            / (a.inv_mass() + b.inv_mass()))
+            * self.normal
+            * *settings.percent_correction;
+        let a_inv_mass = a.inv_mass();
+        let b_inv_mass = b.inv_mass();
 
-    /// When two objects with infinite mass collide, their velocities are set to zero
-    fn infinite_mass_correction(&mut self) {
-        self.a.borrow_mut().kinematics.vel = Vector2::zeros();
-        self.b.borrow_mut().kinematics.vel = Vector2::zeros();
+        a.tx.pos -= correction * a_inv_mass;
+        b.tx.pos += correction * b_inv_mass;
     }
 }
 
@@ -186,7 +337,84 @@ mod tests {
 
     use nalgebra::Point2;
 
-    use crate::{circle::Circle, kinematics::Kinematics, shapes::Shapes, transform::Transform};
+    use crate::{
+        circle::Circle, kinematics::Kinematics, material::Material, object::BodyType,
+        polygon::Polygon, scene::Scene, shapes::Shapes, transform::Transform,
+    };
+
+    /// Builds the vertices of a rectangle centered on the origin.
+    fn rect(half_w: f64, half_h: f64) -> Vec<Point2<f64>> {
+        vec![
+            Point2::new(-half_w, -half_h),
+            Point2::new(half_w, -half_h),
+            Point2::new(half_w, half_h),
+            Point2::new(-half_w, half_h),
+        ]
+    }
+
+    /// Places a unit box at rest on a static incline of the given angle and returns how far the
+    /// box has moved along the slope after two seconds.
+    fn slide_distance_on_incline(angle: f64, friction: f64) -> f64 {
+        let incline = Object::builder(Shapes::Polygon(Polygon::new(rect(50.0, 5.0)).unwrap()))
+            .transform(Transform::with_orientation(Point2::new(0.0, 0.0), angle))
+            .material(Material::new(
+                1.0,
+                OrderedFloat(0.0),
+                OrderedFloat(friction),
+                OrderedFloat(friction),
+            ))
+            .kinematics(Kinematics::new(Vector2::zeros(), 0.0, 0.0))
+            .body_type(BodyType::Static)
+            .build();
+
+        let slope = Vector2::new(angle.cos(), angle.sin());
+        let up = Vector2::new(angle.sin(), -angle.cos());
+        let start = Point2::from(up * 5.99);
+        let block = Object::builder(Shapes::Polygon(Polygon::new(rect(1.0, 1.0)).unwrap()))
+            .transform(Transform::with_orientation(start, angle))
+            .material(Material::new(
+                1.0,
+                OrderedFloat(0.0),
+                OrderedFloat(friction),
+                OrderedFloat(friction),
+            ))
+            .kinematics(Kinematics::new(Vector2::zeros(), 0.0, 0.0))
+            .build();
+
+        let mut scene = Scene::new();
+        scene.add_body(incline);
+        let block = scene.add_body(block);
+
+        for _ in 0..120 {
+            scene.step(1.0 / 60.0);
+        }
+
+        let moved = scene.body(block).unwrap().tx.pos - start;
+        moved.dot(&slope)
+    }
+
+    #[test]
+    fn test_friction_holds_below_static_angle() {
+        // tan(15 deg) ~= 0.27 < 0.5
+        let moved = slide_distance_on_incline(15f64.to_radians(), 0.5);
+
+        assert!(moved.abs() < 0.05, "box slid {moved}");
+    }
+
+    #[test]
+    fn test_friction_slides_above_static_angle() {
+        // tan(40 deg) ~= 0.84 > 0.5
+        let moved = slide_distance_on_incline(40f64.to_radians(), 0.5);
+
+        assert!(moved > 1.0, "box only slid {moved}");
+    }
+
+    #[test]
+    fn test_frictionless_incline_slides() {
+        let moved = slide_distance_on_incline(15f64.to_radians(), 0.0);
+
+        assert!(moved > 1.0, "box only slid {moved}");
+    }
 
     #[test]
     fn test_apply_impulse() {
@@ -198,28 +426,67 @@ mod tests {
         });
         let tx1 = Transform::new(Point2::new(0.0, 0.0));
         let tx2 = Transform::new(Point2::new(0.0, 0.0));
-        let a = RefCell::new(Object::new(
-            circle1,
-            tx1,
-            None,
-            None,
-            Some(Kinematics::new(Vector2::new(10.0, 0.0), 0.0, 0.0)),
-        ));
-        let b = RefCell::new(Object::new(
-            circle2,
-            tx2,
-            None,
-            None,
-            Some(Kinematics::new(Vector2::new(-10.0, 0.0), 0.0, 0.0)),
-        ));
-        let mut manifold = Manifold::new(Rc::new(a), Rc::new(b));
-        let initial_vel_a = manifold.a.borrow().kinematics.vel.clone();
-        let initial_vel_b = manifold.b.borrow().kinematics.vel.clone();
-
-        manifold.solve();
-        manifold.apply_impulse();
-
-        assert_ne!(manifold.a.borrow().kinematics.vel, initial_vel_a);
-        assert_ne!(manifold.b.borrow().kinematics.vel, initial_vel_b);
+        let mut scene = Scene::new();
+        let a = scene.add_body(
+            Object::builder(circle1)
+                .transform(tx1)
+                .kinematics(Kinematics::new(Vector2::new(10.0, 0.0), 0.0, 0.0))
+                .build(),
+        );
+        let b = scene.add_body(
+            Object::builder(circle2)
+                .transform(tx2)
+                .kinematics(Kinematics::new(Vector2::new(-10.0, 0.0), 0.0, 0.0))
+                .build(),
+        );
+        let mut manifold = Manifold::new(a, b);
+        let initial_vel_a = scene.body(a).unwrap().kinematics.vel;
+        let initial_vel_b = scene.body(b).unwrap().kinematics.vel;
+
+        manifold.solve(scene.body(a).unwrap(), scene.body(b).unwrap());
+        let (obj_a, obj_b) = scene.body_pair_mut(a, b).unwrap();
+        manifold.apply_impulse(obj_a, obj_b);
+
+        assert_ne!(scene.body(a).unwrap().kinematics.vel, initial_vel_a);
+        assert_ne!(scene.body(b).unwrap().kinematics.vel, initial_vel_b);
+    }
+
+    /// Returns how much the velocity of the first of two head-on circles changes after one
+    /// impulse, once `modify` has been applied to their manifold.
+    fn head_on_velocity_change(modify: impl FnOnce(&mut Manifold)) -> f64 {
+        let mut scene = Scene::new();
+        let a = scene.add_body(
+            Object::builder(Shapes::Circle(Circle::new(4.0).unwrap()))
+                .kinematics(Kinematics::new(Vector2::new(10.0, 0.0), 0.0, 0.0))
+                .build(),
+        );
+        let b = scene.add_body(
+            Object::builder(Shapes::Circle(Circle::new(4.0).unwrap()))
+                .position(Point2::new(6.0, 0.0))
+                .kinematics(Kinematics::new(Vector2::new(-10.0, 0.0), 0.0, 0.0))
+                .build(),
+        );
+        let mut manifold = Manifold::new(a, b);
+        manifold.solve(scene.body(a).unwrap(), scene.body(b).unwrap());
+        modify(&mut manifold);
+
+        let (obj_a, obj_b) = scene.body_pair_mut(a, b).unwrap();
+        manifold.apply_impulse(obj_a, obj_b);
+
+        10.0 - scene.body(a).unwrap().kinematics.vel.x
+    }
+
+    #[test]
+    fn test_impulse_scale_and_enabled() {
+        let full = head_on_velocity_change(|_| {});
+        let half = head_on_velocity_change(|m| m.impulse_scale = 0.5);
+        let disabled = head_on_velocity_change(|m| m.enabled = false);
+
+        assert!(full > 1.0, "only slowed by {full}");
+        assert!(
+            (half - full / 2.0).abs() < 1e-9,
+            "{half} is not half of {full}"
+        );
+        assert_eq!(disabled, 0.0);
     }
 }
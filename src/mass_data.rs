@@ -7,7 +7,8 @@ pub struct MassData {
     pub mass: Kilogram,
     /// Inverse mass of the object, calculated as 1 divided by the mass.
     pub inv_mass: InvKilogram,
-    // moment_inertia: KilogramMeterSquared,
+    /// Moment of inertia of the object about its center of mass.
+    pub moment_inertia: KilogramMeterSquared,
     /// Inverse moment of inertia of the object, calculated as 1 divided by the moment of inertia.
     pub inv_m_inertia: MeterSquaredPerKilogram,
 }
@@ -34,7 +35,7 @@ impl MassData {
         MassData {
             mass,
             inv_mass,
-            // moment_inertia,
+            moment_inertia,
             inv_m_inertia,
         }
     }
@@ -1,3 +1,6 @@
+use ordered_float::OrderedFloat;
+use rand::Rng;
+
 use crate::types::{KilogramPerCubicMeter, NormalizedCoefficient};
 
 /// Represents material properties of an object affecting its physical behavior.
@@ -38,4 +41,33 @@ impl Material {
             static_friction,
         }
     }
+
+    /// Creates a material with random properties drawn from the given random number generator.
+    ///
+    /// Density is drawn from `[0.1, 10)`, and restitution and both friction coefficients from
+    /// `[0, 1)`. Passing a seeded generator makes the result reproducible.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator to draw from.
+    ///
+    /// # Returns
+    ///
+    /// A new `Material` instance with random material properties.
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Material::new(
+            rng.gen_range(0.1..10.0),
+            OrderedFloat(rng.gen_range(0.0..1.0)),
+            OrderedFloat(rng.gen_range(0.0..1.0)),
+            OrderedFloat(rng.gen_range(0.0..1.0)),
+        )
+    }
+}
+
+impl Default for Material {
+    /// Returns a material with a density of `1.0`, a restitution of `0.2`, a dynamic friction of
+    /// `0.3` and a static friction of `0.5`.
+    fn default() -> Self {
+        Material::new(1.0, OrderedFloat(0.2), OrderedFloat(0.3), OrderedFloat(0.5))
+    }
 }
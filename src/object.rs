@@ -1,95 +1,123 @@
+#[cfg(feature = "render-piston")]
 use graphics::Context;
+#[cfg(feature = "render-piston")]
 use opengl_graphics::GlGraphics;
 
 use nalgebra::Vector2;
 
-use ordered_float::OrderedFloat;
-use rand::Rng;
-
+use crate::aabb::Aabb;
 use crate::custom_math::cross_v_v;
+use crate::fixture::{Fixture, PartId};
 use crate::kinematics::Kinematics;
 use crate::mass_data::MassData;
-use crate::material::Material;
-use crate::shapes::{Shape, Shapes};
+use crate::object_builder::ObjectBuilder;
+use crate::shapes::Shape;
+use crate::shapes::{ConvexPart, Shapes};
 use crate::transform::Transform;
+use crate::types::{InvKilogram, MeterSquaredPerKilogram};
+
+/// How a body takes part in the simulation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BodyType {
+    /// Never moves and is not affected by forces or impulses.
+    Static,
+    /// Moves by its set velocity but is not affected by gravity, forces or impulses.
+    ///
+    /// Useful for moving platforms that push dynamic bodies without being pushed back.
+    Kinematic,
+    /// Fully simulated: affected by gravity, forces and collisions.
+    Dynamic,
+}
 
 /// Represents a physical object in the simulation with shape, material, and kinematic properties.
 pub struct Object {
-    /// The shape of the object, such as a circle or polygon.
-    pub shape: Shapes,
-    /// The transform containing position and orientation information.
+    /// The shapes making up the object, each with its own placement and material.
+    ///
+    /// There is always at least one fixture.
+    pub fixtures: Vec<Fixture>,
+    /// The transform of the object's center of mass.
     pub tx: Transform,
-    /// The material properties of the object affecting its physical behavior.
-    pub mat: Material,
     /// The mass and moment of inertia data for the object.
     pub mass_data: MassData,
     /// The kinematic properties of the object, including velocity, angular velocity, and torque.
     pub kinematics: Kinematics,
     /// The force acting on the object.
     pub force: Vector2<f64>,
+    /// How the object takes part in the simulation.
+    pub body_type: BodyType,
 }
 
 impl Object {
-    /// Creates a new object with the specified shape, transform, material, mass data, and kinematics.
+    /// Starts building a new object with the given shape.
     ///
-    /// If material, mass data, or kinematics are not provided, random values within certain ranges are generated.
+    /// See `ObjectBuilder` for the defaults used for anything not set explicitly.
     ///
     /// # Arguments
     ///
     /// * `shape` - The shape of the object.
-    /// * `tx` - The transform containing position and orientation information.
-    /// * `mat` - Optional material properties of the object. If not provided, random values are generated.
-    /// * `mass_data` - Optional mass and moment of inertia data. If not provided, calculated based on the shape and material.
-    /// * `kinematics` - Optional kinematic properties of the object. If not provided, random values are generated.
+    pub fn builder(shape: Shapes) -> ObjectBuilder {
+        ObjectBuilder::new(shape)
+    }
+
+    /// Iterates over the ids of all convex parts of all fixtures of the object.
+    pub fn parts(&self) -> impl Iterator<Item = PartId> + '_ {
+        self.fixtures.iter().enumerate().flat_map(|(fixture, f)| {
+            (0..f.shape.part_count()).map(move |part| PartId { fixture, part })
+        })
+    }
+
+    /// Returns a convex part of one of the object's fixtures.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the part.
     ///
     /// # Returns
     ///
-    /// A new `Object` instance with the specified properties.
-    pub fn new(
-        mut shape: Shapes,
-        tx: Transform,
-        mat: Option<Material>,
-        mass_data: Option<MassData>,
-        kinematics: Option<Kinematics>,
-    ) -> Self {
-        let mat = mat.unwrap_or_else(|| {
-            // Generate random material properties
-            let density = rand::thread_rng().gen_range(0.1..10.0);
-            let restitution = rand::thread_rng().gen_range(0.0..1.0);
-            let dynamic_friction = rand::thread_rng().gen_range(0.0..1.0);
-            let static_friction = rand::thread_rng().gen_range(0.0..1.0);
-
-            Material::new(
-                density,
-                OrderedFloat(restitution),
-                OrderedFloat(dynamic_friction),
-                OrderedFloat(static_friction),
-            )
-        });
+    /// The part along with the transform that brings it into world space.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the id does not refer to a part of this object.
+    pub fn part(&self, id: PartId) -> (ConvexPart<'_>, Transform) {
+        let fixture = &self.fixtures[id.fixture];
 
-        let mass_data = mass_data.unwrap_or_else(|| shape.calculate_mass_data(mat.density));
+        (
+            fixture.shape.part(id.part),
+            fixture.world_transform(&self.tx),
+        )
+    }
 
-        let kinematics = kinematics.unwrap_or_else(|| {
-            // Generate random kinematics properties
-            let vel = Vector2::new(
-                rand::thread_rng().gen_range(-10.0..10.0),
-                rand::thread_rng().gen_range(-10.0..10.0),
-            );
-            let angular_vel = rand::thread_rng().gen_range(-1.0..1.0);
-            let torque = rand::thread_rng().gen_range(-1.0..1.0);
+    /// Calculates the world space bounding box of all of the object's fixtures.
+    pub fn bounding_box(&self) -> Aabb {
+        self.fixtures
+            .iter()
+            .map(|f| f.shape.bounding_box(&f.world_transform(&self.tx)))
+            .reduce(|a, b| a.union(&b))
+            .expect("an object has at least one fixture")
+    }
 
-            Kinematics::new(vel, angular_vel, torque)
-        });
+    /// Returns `true` if the object is affected by forces and impulses.
+    pub fn is_dynamic(&self) -> bool {
+        self.body_type == BodyType::Dynamic
+    }
 
-        let force = Vector2::zeros();
+    /// Returns the inverse mass the solver sees, which is zero for static and kinematic bodies.
+    pub fn inv_mass(&self) -> InvKilogram {
+        if self.is_dynamic() {
+            self.mass_data.inv_mass
+        } else {
+            0.0
+        }
+    }
 
-        Object {
-            shape,
-            tx,
-            mat,
-            mass_data,
-            kinematics,
-            force,
+    /// Returns the inverse moment of inertia the solver sees, which is zero for static and
+    /// kinematic bodies.
+    pub fn inv_m_inertia(&self) -> MeterSquaredPerKilogram {
+        if self.is_dynamic() {
+            self.mass_data.inv_m_inertia
+        } else {
+            0.0
         }
     }
 
@@ -104,17 +132,22 @@ impl Object {
     /// * `imp` - The impulse vector to apply.
     /// * `contact_vec` - The vector from the object's center to the contact point.
     pub fn apply_impulse(&mut self, imp: &Vector2<f64>, contact_vec: &Vector2<f64>) {
-        self.kinematics.vel += self.mass_data.inv_mass * imp;
-        self.kinematics.angular_vel += self.mass_data.inv_m_inertia * cross_v_v(contact_vec, imp);
+        self.kinematics.vel += self.inv_mass() * imp;
+        self.kinematics.angular_vel += self.inv_m_inertia() * cross_v_v(contact_vec, imp);
     }
 
-    /// Dispatches the draw call to the specific shape implementation.
+    /// Dispatches the draw call to the shape implementation of each fixture.
     ///
     /// # Arguments
     ///
     /// * `c` - The graphics context.
     /// * `gl` - The OpenGL graphics.
+    #[cfg(feature = "render-piston")]
     pub fn draw(&self, c: Context, gl: &mut GlGraphics) {
-        self.shape.draw(c, gl, &self.tx);
+        for fixture in &self.fixtures {
+            fixture
+                .shape
+                .draw(c, gl, &fixture.world_transform(&self.tx));
+        }
     }
 }
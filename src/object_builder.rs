@@ -0,0 +1,234 @@
+use nalgebra::{Point2, Vector2};
+use rand::Rng;
+
+use crate::collision_filter::CollisionFilter;
+use crate::fixture::{combined_mass_data, Fixture};
+use crate::kinematics::Kinematics;
+use crate::mass_data::MassData;
+use crate::material::Material;
+use crate::object::{BodyType, Object};
+use crate::shapes::Shapes;
+use crate::transform::Transform;
+use crate::types::{Radian, RadianPerSec};
+
+/// Builds an `Object` from a shape, with explicit defaults for everything else.
+///
+/// Unless set otherwise, an object is:
+///
+/// * dynamic,
+/// * at the origin with no rotation,
+/// * made of `Material::default()`,
+/// * colliding with everything, through `CollisionFilter::default()`,
+/// * at rest, with no torque,
+/// * made of a single fixture, the shape passed to `new`,
+/// * given mass data computed from its fixtures and their materials' densities.
+///
+/// Nothing is random unless the caller asks for it by passing in a random number generator, so
+/// identical inputs always produce identical objects.
+pub struct ObjectBuilder {
+    /// The fixtures of the object, starting with the one made from the shape passed to `new`.
+    fixtures: Vec<Fixture>,
+    /// The transform of the object.
+    tx: Transform,
+    /// Explicit mass data, overriding the one computed from the shape.
+    mass_data: Option<MassData>,
+    /// The kinematic properties of the object.
+    kinematics: Kinematics,
+    /// How the object takes part in the simulation.
+    body_type: BodyType,
+}
+
+impl ObjectBuilder {
+    /// Creates a new builder for an object with the given shape.
+    ///
+    /// # Arguments
+    ///
+    /// * `shape` - The shape of the object.
+    pub fn new(shape: Shapes) -> Self {
+        ObjectBuilder {
+            fixtures: vec![Fixture::new(
+                shape,
+                Transform::default(),
+                Material::default(),
+            )],
+            tx: Transform::default(),
+            mass_data: None,
+            kinematics: Kinematics::default(),
+            body_type: BodyType::Dynamic,
+        }
+    }
+
+    /// Sets the transform of the object.
+    pub fn transform(mut self, tx: Transform) -> Self {
+        self.tx = tx;
+        self
+    }
+
+    /// Sets the position of the object, keeping its rotation.
+    pub fn position(mut self, pos: Point2<f64>) -> Self {
+        self.tx.pos = pos;
+        self
+    }
+
+    /// Sets the orientation of the object in radians, keeping its position.
+    pub fn orientation(mut self, orientation: Radian) -> Self {
+        self.tx = Transform::with_orientation(self.tx.pos, orientation);
+        self
+    }
+
+    /// Sets the material of the first fixture, the one made from the shape passed to `new`.
+    pub fn material(mut self, mat: Material) -> Self {
+        self.fixtures[0].mat = mat;
+        self
+    }
+
+    /// Sets the collision filter of every fixture added so far.
+    ///
+    /// Fixtures added afterwards keep their own filters.
+    pub fn filter(mut self, filter: CollisionFilter) -> Self {
+        for fixture in &mut self.fixtures {
+            fixture.filter = filter;
+        }
+        self
+    }
+
+    /// Sets whether every fixture added so far is a sensor.
+    ///
+    /// Fixtures added afterwards keep their own setting. See `Fixture::with_sensor`.
+    pub fn sensor(mut self, is_sensor: bool) -> Self {
+        for fixture in &mut self.fixtures {
+            fixture.is_sensor = is_sensor;
+        }
+        self
+    }
+
+    /// Adds another fixture to the object.
+    ///
+    /// The fixture's placement is relative to the object's position. Once all fixtures are in
+    /// place, they are moved together so that the object's position is its center of mass.
+    pub fn fixture(mut self, fixture: Fixture) -> Self {
+        self.fixtures.push(fixture);
+        self
+    }
+
+    /// Sets the mass data of the object, instead of computing it from the fixtures.
+    ///
+    /// Computing the mass data recenters the fixtures on the center of mass, so fixtures of an
+    /// object given explicit mass data keep their shapes and placements as passed in.
+    pub fn mass_data(mut self, mass_data: MassData) -> Self {
+        self.mass_data = Some(mass_data);
+        self
+    }
+
+    /// Sets the kinematic properties of the object.
+    pub fn kinematics(mut self, kinematics: Kinematics) -> Self {
+        self.kinematics = kinematics;
+        self
+    }
+
+    /// Sets the linear velocity of the object.
+    pub fn velocity(mut self, vel: Vector2<f64>) -> Self {
+        self.kinematics.vel = vel;
+        self
+    }
+
+    /// Sets the angular velocity of the object.
+    pub fn angular_velocity(mut self, angular_vel: RadianPerSec) -> Self {
+        self.kinematics.angular_vel = angular_vel;
+        self
+    }
+
+    /// Sets how the object takes part in the simulation.
+    pub fn body_type(mut self, body_type: BodyType) -> Self {
+        self.body_type = body_type;
+        self
+    }
+
+    /// Replaces the material of the first fixture with a random one drawn from `rng`.
+    ///
+    /// See `Material::random` for the ranges used.
+    pub fn random_material<R: Rng + ?Sized>(mut self, rng: &mut R) -> Self {
+        self.fixtures[0].mat = Material::random(rng);
+        self
+    }
+
+    /// Replaces the kinematics with random ones drawn from `rng`.
+    ///
+    /// See `Kinematics::random` for the ranges used.
+    pub fn random_kinematics<R: Rng + ?Sized>(mut self, rng: &mut R) -> Self {
+        self.kinematics = Kinematics::random(rng);
+        self
+    }
+
+    /// Builds the object.
+    pub fn build(self) -> Object {
+        let ObjectBuilder {
+            mut fixtures,
+            tx,
+            mass_data,
+            kinematics,
+            body_type,
+        } = self;
+
+        let mass_data = mass_data.unwrap_or_else(|| combined_mass_data(&mut fixtures));
+
+        Object {
+            fixtures,
+            tx,
+            mass_data,
+            kinematics,
+            force: Vector2::zeros(),
+            body_type,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ordered_float::OrderedFloat;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::circle::Circle;
+
+    fn circle() -> Shapes {
+        Shapes::Circle(Circle {
+            radius: OrderedFloat(1.0),
+        })
+    }
+
+    #[test]
+    fn test_defaults() {
+        let obj = ObjectBuilder::new(circle()).build();
+
+        assert_eq!(obj.body_type, BodyType::Dynamic);
+        assert_eq!(obj.tx.pos, Point2::origin());
+        assert_eq!(obj.tx.orientation(), 0.0);
+        assert_eq!(obj.fixtures[0].mat.density, Material::default().density);
+        assert_eq!(obj.kinematics.vel, Vector2::zeros());
+        assert_eq!(obj.kinematics.angular_vel, 0.0);
+        assert_eq!(obj.kinematics.torque, 0.0);
+        assert_eq!(obj.mass_data.mass, std::f64::consts::PI);
+    }
+
+    #[test]
+    fn test_seeded_randomness_is_reproducible() {
+        let build = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            ObjectBuilder::new(circle())
+                .random_material(&mut rng)
+                .random_kinematics(&mut rng)
+                .build()
+        };
+
+        let (a, b, c) = (build(7), build(7), build(8));
+
+        assert_eq!(a.fixtures[0].mat.density, b.fixtures[0].mat.density);
+        assert_eq!(a.fixtures[0].mat.restitution, b.fixtures[0].mat.restitution);
+        assert_eq!(a.kinematics.vel, b.kinematics.vel);
+        assert_eq!(a.kinematics.angular_vel, b.kinematics.angular_vel);
+        assert_ne!(a.kinematics.vel, c.kinematics.vel);
+    }
+}
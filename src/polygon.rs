@@ -1,20 +1,27 @@
+#[cfg(feature = "render-piston")]
 use graphics::{line::Line, Context, Transformed};
+#[cfg(feature = "render-piston")]
 use opengl_graphics::GlGraphics;
 
-use nalgebra::{Matrix2, Point2, Vector2};
-use std::f64::EPSILON;
+use nalgebra::{Point2, Vector2};
 
-use crate::constants::{ONE_THIRD, WHITE};
+use crate::aabb::Aabb;
+#[cfg(feature = "render-piston")]
+use crate::constants::WHITE;
+use crate::constants::{GEOMETRY_TOLERANCE, ONE_THIRD};
 use crate::custom_math::cross_v_v;
 use crate::mass_data::MassData;
+use crate::ray::Ray;
+use crate::shape_error::ShapeError;
 use crate::shapes::{Shape, ShapeDiscriminant};
 use crate::transform::Transform;
 use crate::types::KilogramPerCubicMeter;
 
 /// Represents a polygon in the simulation.
+///
+/// The vertices and normals are stored in model space; the owning object's `Transform` places them
+/// in the world.
 pub struct Polygon {
-    /// The orientation matrix of the polygon.
-    pub orient: Matrix2<f64>,
     /// The vertices of the polygon.
     pub vertices: Vec<Point2<f64>>,
     /// The normals of the polygon.
@@ -22,39 +29,80 @@ pub struct Polygon {
 }
 
 impl Polygon {
-    /// Creates a new polygon with the specified vertices and orientation.
+    /// Creates a new polygon from the convex hull of the specified points.
+    ///
+    /// The points may be given in any order. Points inside the hull or on one of its edges are
+    /// dropped, and the hull is stored counter-clockwise so that the computed normals point
+    /// outwards.
     ///
     /// # Arguments
     ///
-    /// * `vertices` - The vertices of the polygon.
-    /// * `orient` - Optional orientation matrix. If not provided, the identity matrix is used.
+    /// * `points` - The points to wrap.
     ///
     /// # Returns
     ///
-    /// A new `Polygon` instance.
-    pub fn new(vertices: Vec<Point2<f64>>, orient: Option<Matrix2<f64>>) -> Self {
-        let orient = orient.unwrap_or_else(Matrix2::identity);
-
+    /// A new `Polygon` instance, or a `ShapeError` if the points do not span a hull with a
+    /// non-zero area.
+    pub fn new(points: Vec<Point2<f64>>) -> Result<Self, ShapeError> {
         // Ensure enough vertices to make polygon
-        if vertices.len() <= 2 {
-            todo!("Error")
+        if points.len() < 3 {
+            return Err(ShapeError::TooFewVertices {
+                count: points.len(),
+            });
         }
 
-        // let right_most = get_right_most_vert_idx(&vertices);
-        // TODO
-        // let hull = build_hull(&vertices, right_most);
-        let normals = compute_norms(&vertices);
+        if let Some(index) = points
+            .iter()
+            .position(|v| !v.x.is_finite() || !v.y.is_finite())
+        {
+            return Err(ShapeError::NonFiniteVertex { index });
+        }
 
-        Self {
-            orient,
-            vertices,
-            normals,
+        let points = dedup_points(points);
+        if points.len() < 3 {
+            return Err(ShapeError::ZeroArea);
         }
+
+        let right_most = get_right_most_vert_idx(&points);
+        let vertices = build_hull(&points, right_most);
+
+        if vertices.len() < 3 || signed_area(&vertices) <= GEOMETRY_TOLERANCE {
+            return Err(ShapeError::ZeroArea);
+        }
+
+        let normals = compute_norms(&vertices);
+
+        Ok(Self { vertices, normals })
     }
 
-    // fn set_bounding_box(&mut self) {
-    //     unimplemented!()
-    // }
+    /// Creates a new polygon from vertices that already describe a convex polygon.
+    ///
+    /// Unlike `new`, the vertices are kept as given instead of being wrapped in a hull, apart from
+    /// being reversed if they wind clockwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertices` - The vertices of the polygon, in either winding order.
+    ///
+    /// # Returns
+    ///
+    /// A new `Polygon` instance, or a `ShapeError` describing why the vertices do not form a
+    /// convex polygon.
+    pub fn from_convex(mut vertices: Vec<Point2<f64>>) -> Result<Self, ShapeError> {
+        validate(&vertices)?;
+
+        if signed_area(&vertices) < 0.0 {
+            vertices.reverse();
+        }
+
+        if let Some(index) = find_reflex_vertex(&vertices) {
+            return Err(ShapeError::NotConvex { index });
+        }
+
+        let normals = compute_norms(&vertices);
+
+        Ok(Self { vertices, normals })
+    }
 
     /// Finds the support point of the polygon in the given direction.
     ///
@@ -68,9 +116,25 @@ impl Polygon {
     pub fn find_support(&self, dir: &Vector2<f64>) -> Point2<f64> {
         self.vertices
             .iter()
-            .max_by(|v1, v2| v1.coords.dot(dir).partial_cmp(&v2.coords.dot(dir)).unwrap())
+            .max_by(|v1, v2| v1.coords.dot(dir).total_cmp(&v2.coords.dot(dir)))
             .map_or(Point2::new(0.0, 0.0), |v| *v)
     }
+
+    /// Returns `true` if a world space point lies inside or on the polygon.
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - The point, in world space.
+    /// * `tx` - The transformation to apply to the shape.
+    pub fn contains_point(&self, p: &Point2<f64>, tx: &Transform) -> bool {
+        let p = tx.to_local(p);
+
+        // Inside a convex polygon means behind every edge
+        self.vertices
+            .iter()
+            .zip(&self.normals)
+            .all(|(v, n)| n.dot(&(p - v)) <= 0.0)
+    }
 }
 
 impl Shape for Polygon {
@@ -85,41 +149,81 @@ impl Shape for Polygon {
     ///
     /// A `MassData` structure containing the calculated mass and moment of inertia.
     fn calculate_mass_data(&mut self, density: KilogramPerCubicMeter) -> MassData {
-        let mut centroid = Vector2::zeros();
-        let mut area = 0.0;
-        let mut mmi = 0.0;
+        let (area, centroid, second_moment) = area_moments(&self.vertices);
 
-        // Calculate area and centroid
-        for (p1, p2) in self
-            .vertices
-            .iter()
-            .zip(self.vertices.iter().cycle().skip(1))
-        {
-            // Get the signed area of the triangle formed by (0, 0), p1, and p2
-            let signed_area = cross_v_v(&p1.coords, &p2.coords);
-            let tri_area = 0.5 * signed_area;
+        // Translate vertices to be centered around the origin
+        for vert in &mut self.vertices {
+            vert.coords -= centroid;
+        }
 
-            area += tri_area;
-            // Accumulate the weighted centroid coordinates.
-            centroid += tri_area * ONE_THIRD * (p1.coords + p2.coords);
+        // Parallel axis theorem: move the second moment from the old origin to the centroid
+        let mmi = second_moment - area * centroid.norm_squared();
 
-            // Calculate squared integral terms for inertia calculation.
-            let int_x_sqrd =
-                p1.coords.x * p1.coords.x + p2.coords.x * p1.coords.x + p2.coords.x * p2.coords.x;
-            let int_y_sqrd =
-                p1.coords.y * p1.coords.y + p2.coords.y * p1.coords.y + p2.coords.y * p2.coords.y;
+        MassData::new(density * area, mmi * density)
+    }
 
-            mmi += (0.25 * ONE_THIRD * signed_area) * (int_x_sqrd + int_y_sqrd)
-        }
+    /// Returns the centroid of the polygon in model space.
+    fn centroid(&self) -> Point2<f64> {
+        Point2::from(area_moments(&self.vertices).1)
+    }
 
-        centroid *= 1.0 / area;
+    /// Calculates the world space bounding box of the polygon.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transform that brings the polygon into world space.
+    fn bounding_box(&self, tx: &Transform) -> Aabb {
+        Aabb::from_points(self.vertices.iter().map(|v| tx.to_world(v)))
+            .expect("a polygon has at least three vertices")
+    }
 
-        // Translate vertices to be centered around the origin
-        for vert in &mut self.vertices {
-            vert.coords -= centroid;
+    /// Finds where a ray first enters the polygon.
+    ///
+    /// The ray is clipped against every edge in model space. A ray starting inside the polygon
+    /// does not hit it.
+    ///
+    /// # Arguments
+    ///
+    /// * `ray` - The ray, in world space.
+    /// * `tx` - The transformation to apply to the shape.
+    ///
+    /// # Returns
+    ///
+    /// The distance along the ray and the world space normal of the edge that was hit, or `None`
+    /// if the ray misses within its maximum distance.
+    fn ray_cast(&self, ray: &Ray, tx: &Transform) -> Option<(f64, Vector2<f64>)> {
+        let origin = tx.to_local(&ray.origin);
+        let dir = tx.rotate_to_local(&ray.dir);
+
+        let mut lower = 0.0;
+        let mut upper = ray.max_distance;
+        let mut hit_edge = None;
+
+        for (v, n) in self.vertices.iter().zip(&self.normals) {
+            // The ray is inside this edge's half-plane for distances where num >= denom * t
+            let num = n.dot(&(v - origin));
+            let denom = n.dot(&dir);
+
+            if denom == 0.0 {
+                // Parallel to the edge and outside it
+                if num < 0.0 {
+                    return None;
+                }
+            } else if denom < 0.0 && num < lower * denom {
+                // Entering the half-plane
+                lower = num / denom;
+                hit_edge = Some(n);
+            } else if denom > 0.0 && num < upper * denom {
+                // Leaving the half-plane
+                upper = num / denom;
+            }
+
+            if upper < lower {
+                return None;
+            }
         }
 
-        MassData::new(density * -area, -mmi * density)
+        hit_edge.map(|n| (lower, tx.rotate_to_world(n)))
     }
 
     /// Draws the polygon using OpenGL graphics.
@@ -129,6 +233,7 @@ impl Shape for Polygon {
     /// * `c` - The graphics context.
     /// * `gl` - The OpenGL graphics.
     /// * `tx` - The transform information.
+    #[cfg(feature = "render-piston")]
     fn draw(&self, c: Context, gl: &mut GlGraphics, tx: &Transform) {
         // piston polygon only has a filled version
         for i in 0..self.vertices.len() {
@@ -139,7 +244,9 @@ impl Shape for Polygon {
             Line::new(WHITE, 1.0).draw(
                 [start[0], start[1], end[0], end[1]],
                 &c.draw_state,
-                c.transform.trans(tx.pos.x, tx.pos.y),
+                c.transform
+                    .trans(tx.pos.x, tx.pos.y)
+                    .rot_rad(tx.orientation()),
                 gl,
             );
         }
@@ -151,57 +258,289 @@ impl Shape for Polygon {
     }
 }
 
-// fn get_right_most_vert_idx(verts: &[Point2<f64>]) -> usize {
-//     let (right_most, _) =
-//         verts
-//             .iter()
-//             .enumerate()
-//             .fold((0, verts[0].x), |(right_most, highest_x), (i, vertex)| {
-//                 if vertex.x > highest_x {
-//                     (i, vertex.x)
-//                 } else if vertex.x == highest_x && vertex.y < verts[right_most].y {
-//                     (i, highest_x)
-//                 } else {
-//                     (right_most, highest_x)
-//                 }
-//             });
-
-//     right_most
-// }
+/// Removes points that lie within `GEOMETRY_TOLERANCE` of an earlier point.
+///
+/// # Arguments
+///
+/// * `points` - The points to filter.
+///
+/// # Returns
+///
+/// The remaining points, in their original order.
+fn dedup_points(points: Vec<Point2<f64>>) -> Vec<Point2<f64>> {
+    let mut unique: Vec<Point2<f64>> = Vec::with_capacity(points.len());
+
+    for p in points {
+        if unique
+            .iter()
+            .all(|u| (p - u).norm_squared() > GEOMETRY_TOLERANCE * GEOMETRY_TOLERANCE)
+        {
+            unique.push(p);
+        }
+    }
+
+    unique
+}
+
+/// Finds the index of the right most vertex, taking the lowest one if several share the same x.
+///
+/// # Arguments
+///
+/// * `verts` - The vertices to search.
+///
+/// # Returns
+///
+/// The index of the right most vertex, which is always on the convex hull.
+fn get_right_most_vert_idx(verts: &[Point2<f64>]) -> usize {
+    let (right_most, _) =
+        verts
+            .iter()
+            .enumerate()
+            .fold((0, verts[0].x), |(right_most, highest_x), (i, vertex)| {
+                if vertex.x > highest_x {
+                    (i, vertex.x)
+                } else if vertex.x == highest_x && vertex.y < verts[right_most].y {
+                    (i, highest_x)
+                } else {
+                    (right_most, highest_x)
+                }
+            });
+
+    right_most
+}
+
+// Adapted from https://code.tutsplus.com/series/how-to-create-a-custom-physics-engine--gamedev-12715
+/// Builds the convex hull of a set of points using gift wrapping.
+///
+/// Points on a hull edge are skipped in favour of the edge's far end point, so the hull has no
+/// collinear vertices.
+///
+/// # Arguments
+///
+/// * `verts` - The points to wrap, without duplicates.
+/// * `right_most_idx` - The index of the right most point, where wrapping starts.
+///
+/// # Returns
+///
+/// The hull vertices in counter-clockwise order.
+fn build_hull(verts: &[Point2<f64>], right_most_idx: usize) -> Vec<Point2<f64>> {
+    let mut hull = Vec::new();
+    let mut index_hull = right_most_idx;
+
+    loop {
+        hull.push(verts[index_hull]);
+
+        let current = verts[index_hull];
+        let mut next_hull_index = if index_hull == 0 { 1 } else { 0 };
+        for (i, &candidate) in verts.iter().enumerate() {
+            if i == index_hull || i == next_hull_index {
+                continue;
+            }
+
+            // Pick the candidate if it is to the right of the current edge, or further along it
+            let e1 = verts[next_hull_index] - current;
+            let e2 = candidate - current;
+            let c = cross_v_v(&e1, &e2);
+            let tolerance = GEOMETRY_TOLERANCE * e1.norm() * e2.norm();
+
+            if c < -tolerance || (c <= tolerance && e2.norm_squared() > e1.norm_squared()) {
+                next_hull_index = i;
+            }
+        }
+
+        // The length check guards against tolerance issues making the wrap miss its start
+        if next_hull_index == right_most_idx || hull.len() == verts.len() {
+            break;
+        }
+
+        index_hull = next_hull_index;
+    }
+
+    hull
+}
+
+/// Finds a vertex where a counter-clockwise polygon turns clockwise.
+///
+/// # Arguments
+///
+/// * `verts` - The vertices of the polygon, in counter-clockwise order.
+///
+/// # Returns
+///
+/// The index of the first reflex vertex, or `None` if the polygon is convex.
+fn find_reflex_vertex(verts: &[Point2<f64>]) -> Option<usize> {
+    let n = verts.len();
+
+    (0..n).find(|&index| {
+        let e1 = verts[index] - verts[(index + n - 1) % n];
+        let e2 = verts[(index + 1) % n] - verts[index];
+
+        cross_v_v(&e1, &e2) < 0.0
+    })
+}
 
 // Adapted from https://code.tutsplus.com/series/how-to-create-a-custom-physics-engine--gamedev-12715
-// fn build_hull(verts: &[Point2<f64>], right_most_idx: usize) -> Vec<Point2<f64>> {
-//     let mut hull = Vec::new();
-//     let mut index_hull = right_most_idx;
+/// Integrates the area, centroid and polar second moment of area of a simple polygon.
+///
+/// The polygon does not have to be convex, but the vertices must be counter-clockwise.
+///
+/// # Arguments
+///
+/// * `verts` - The vertices of the polygon.
+///
+/// # Returns
+///
+/// A tuple of the area, the centroid and the second moment of area about the model space origin.
+pub(crate) fn area_moments(verts: &[Point2<f64>]) -> (f64, Vector2<f64>, f64) {
+    let mut centroid = Vector2::zeros();
+    let mut area = 0.0;
+    let mut second_moment = 0.0;
+
+    for (p1, p2) in verts.iter().zip(verts.iter().cycle().skip(1)) {
+        // Get the signed area of the triangle formed by (0, 0), p1, and p2
+        let signed_area = cross_v_v(&p1.coords, &p2.coords);
+        let tri_area = 0.5 * signed_area;
+
+        area += tri_area;
+        // Accumulate the weighted centroid coordinates.
+        centroid += tri_area * ONE_THIRD * (p1.coords + p2.coords);
+
+        // Calculate squared integral terms for inertia calculation.
+        let int_x_sqrd =
+            p1.coords.x * p1.coords.x + p2.coords.x * p1.coords.x + p2.coords.x * p2.coords.x;
+        let int_y_sqrd =
+            p1.coords.y * p1.coords.y + p2.coords.y * p1.coords.y + p2.coords.y * p2.coords.y;
+
+        second_moment += (0.25 * ONE_THIRD * signed_area) * (int_x_sqrd + int_y_sqrd)
+    }
+
+    centroid *= 1.0 / area;
+
+    (area, centroid, second_moment)
+}
+
+/// Checks that the vertices describe a simple polygon with a non-zero area.
+///
+/// # Arguments
+///
+/// * `verts` - The vertices of the polygon, in either winding order.
+///
+/// # Returns
+///
+/// The first problem found, if any.
+pub(crate) fn validate(verts: &[Point2<f64>]) -> Result<(), ShapeError> {
+    let n = verts.len();
+
+    // Ensure enough vertices to make polygon
+    if n < 3 {
+        return Err(ShapeError::TooFewVertices { count: n });
+    }
+
+    if let Some(index) = verts
+        .iter()
+        .position(|v| !v.x.is_finite() || !v.y.is_finite())
+    {
+        return Err(ShapeError::NonFiniteVertex { index });
+    }
+
+    for first in 0..n {
+        for second in first + 1..n {
+            if (verts[second] - verts[first]).norm_squared()
+                <= GEOMETRY_TOLERANCE * GEOMETRY_TOLERANCE
+            {
+                return Err(ShapeError::DuplicateVertices { first, second });
+            }
+        }
+    }
 
-//     loop {
-//         hull.push(verts[index_hull]);
+    for index in 0..n {
+        let prev = verts[(index + n - 1) % n];
+        let next = verts[(index + 1) % n];
+        let e1 = verts[index] - prev;
+        let e2 = next - verts[index];
 
-//         let mut next_hull_index = 0;
-//         for i in 1..verts.len() {
-//             if next_hull_index == index_hull {
-//                 next_hull_index = i;
-//                 continue;
-//             }
+        if cross_v_v(&e1, &e2).abs() <= GEOMETRY_TOLERANCE * e1.norm() * e2.norm() {
+            return Err(ShapeError::CollinearVertices { index });
+        }
+    }
 
-//             let e1 = verts[next_hull_index] - verts[hull.len() - 1];
-//             let e2 = verts[i] - verts[hull.len() - 1];
-//             let c = cross_v_v(&e1, &e2);
+    if signed_area(verts).abs() <= GEOMETRY_TOLERANCE {
+        return Err(ShapeError::ZeroArea);
+    }
 
-//             if c < 0.0 || (c == 0.0 && e2.norm_squared() > e1.norm_squared()) {
-//                 next_hull_index = i;
-//             }
-//         }
+    // Adjacent edges share a vertex, so only edges at least two apart can cross
+    for first_edge in 0..n {
+        for second_edge in first_edge + 2..n {
+            if first_edge == 0 && second_edge == n - 1 {
+                continue;
+            }
 
-//         if next_hull_index == right_most_idx {
-//             break;
-//         }
+            if segments_intersect(
+                verts[first_edge],
+                verts[(first_edge + 1) % n],
+                verts[second_edge],
+                verts[(second_edge + 1) % n],
+            ) {
+                return Err(ShapeError::SelfIntersecting {
+                    first_edge,
+                    second_edge,
+                });
+            }
+        }
+    }
 
-//         index_hull = next_hull_index;
-//     }
+    Ok(())
+}
 
-//     hull
-// }
+/// Computes the signed area of a polygon using the shoelace formula.
+///
+/// # Arguments
+///
+/// * `verts` - The vertices of the polygon.
+///
+/// # Returns
+///
+/// The area, positive for counter-clockwise vertices and negative for clockwise ones.
+pub(crate) fn signed_area(verts: &[Point2<f64>]) -> f64 {
+    0.5 * verts
+        .iter()
+        .zip(verts.iter().cycle().skip(1))
+        .map(|(p1, p2)| cross_v_v(&p1.coords, &p2.coords))
+        .sum::<f64>()
+}
+
+/// Checks whether two closed line segments touch or cross.
+///
+/// # Arguments
+///
+/// * `p1`, `p2` - The end points of the first segment.
+/// * `q1`, `q2` - The end points of the second segment.
+fn segments_intersect(p1: Point2<f64>, p2: Point2<f64>, q1: Point2<f64>, q2: Point2<f64>) -> bool {
+    let d1 = cross_v_v(&(p2 - p1), &(q1 - p1));
+    let d2 = cross_v_v(&(p2 - p1), &(q2 - p1));
+    let d3 = cross_v_v(&(q2 - q1), &(p1 - q1));
+    let d4 = cross_v_v(&(q2 - q1), &(p2 - q1));
+
+    if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0))
+        && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+    {
+        return true;
+    }
+
+    // Collinear cases: an end point lying on the other segment
+    let on_segment = |a: Point2<f64>, b: Point2<f64>, p: Point2<f64>, d: f64| {
+        d == 0.0
+            && p.x >= a.x.min(b.x)
+            && p.x <= a.x.max(b.x)
+            && p.y >= a.y.min(b.y)
+            && p.y <= a.y.max(b.y)
+    };
+
+    on_segment(p1, p2, q1, d1)
+        || on_segment(p1, p2, q2, d2)
+        || on_segment(q1, q2, p1, d3)
+        || on_segment(q1, q2, p2, d4)
+}
 
 /// Computes the normals for the edges of a polygon.
 ///
@@ -218,8 +557,242 @@ fn compute_norms(verts: &[Point2<f64>]) -> Vec<Vector2<f64>> {
         .zip(verts.iter().cycle().skip(1))
         .map(|(&p1, &p2)| {
             let face = p2 - p1;
-            assert!(face.norm_squared() > EPSILON * EPSILON);
+            // Degenerate edges are rejected by `validate`
+            debug_assert!(face.norm_squared() > f64::EPSILON * f64::EPSILON);
             Vector2::new(face.y, -face.x).normalize()
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(coords: &[(f64, f64)]) -> Vec<Point2<f64>> {
+        coords.iter().map(|&(x, y)| Point2::new(x, y)).collect()
+    }
+
+    #[test]
+    fn test_new_too_few_vertices() {
+        let res = Polygon::new(points(&[(0.0, 0.0), (1.0, 0.0)]));
+
+        assert_eq!(res.err(), Some(ShapeError::TooFewVertices { count: 2 }));
+    }
+
+    #[test]
+    fn test_new_non_finite_vertex() {
+        let res = Polygon::new(points(&[(0.0, 0.0), (f64::NAN, 0.0), (0.0, 1.0)]));
+
+        assert_eq!(res.err(), Some(ShapeError::NonFiniteVertex { index: 1 }));
+    }
+
+    #[test]
+    fn test_from_convex_duplicate_vertices() {
+        let res = Polygon::from_convex(points(&[(0.0, 0.0), (1.0, 0.0), (1.0, 0.0), (0.0, 1.0)]));
+
+        assert_eq!(
+            res.err(),
+            Some(ShapeError::DuplicateVertices {
+                first: 1,
+                second: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_from_convex_collinear_vertices() {
+        let res = Polygon::from_convex(points(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (0.0, 1.0)]));
+
+        assert_eq!(res.err(), Some(ShapeError::CollinearVertices { index: 1 }));
+    }
+
+    #[test]
+    fn test_from_convex_zero_area() {
+        // A symmetric bow tie, whose two lobes cancel out
+        let res = Polygon::from_convex(points(&[(0.0, 0.0), (2.0, 2.0), (2.0, 0.0), (0.0, 2.0)]));
+
+        assert_eq!(res.err(), Some(ShapeError::ZeroArea));
+    }
+
+    #[test]
+    fn test_from_convex_self_intersecting() {
+        let res = Polygon::from_convex(points(&[(0.0, 0.0), (3.0, 2.0), (3.0, 0.0), (0.0, 3.0)]));
+
+        assert_eq!(
+            res.err(),
+            Some(ShapeError::SelfIntersecting {
+                first_edge: 0,
+                second_edge: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_new_clockwise_is_reoriented() {
+        let mut poly =
+            Polygon::new(points(&[(0.0, 0.0), (0.0, 2.0), (2.0, 2.0), (2.0, 0.0)])).unwrap();
+
+        assert!(signed_area(&poly.vertices) > 0.0);
+
+        // Every normal points away from the center
+        let center = Point2::new(1.0, 1.0);
+        for (v, n) in poly.vertices.iter().zip(&poly.normals) {
+            assert!(n.dot(&(v - center)) > 0.0);
+        }
+
+        let mass_data = poly.calculate_mass_data(1.0);
+        assert!((mass_data.mass - 4.0).abs() < 1e-9);
+        assert!(mass_data.inv_m_inertia > 0.0);
+    }
+
+    #[test]
+    fn test_new_drops_interior_points() {
+        let poly = Polygon::new(points(&[
+            (0.5, 0.5),
+            (0.0, 0.0),
+            (1.5, 0.2),
+            (2.0, 0.0),
+            (1.0, 1.0),
+            (2.0, 2.0),
+            (0.0, 2.0),
+        ]))
+        .unwrap();
+
+        // Wrapping starts at the lowest right most point and goes counter-clockwise
+        assert_eq!(
+            poly.vertices,
+            points(&[(2.0, 0.0), (2.0, 2.0), (0.0, 2.0), (0.0, 0.0)])
+        );
+        assert_eq!(poly.normals.len(), 4);
+    }
+
+    #[test]
+    fn test_new_drops_collinear_points() {
+        let poly = Polygon::new(points(&[
+            (0.0, 0.0),
+            (1.0, 0.0),
+            (2.0, 0.0),
+            (2.0, 1.0),
+            (2.0, 2.0),
+            (1.0, 1.0),
+        ]))
+        .unwrap();
+
+        assert_eq!(poly.vertices, points(&[(2.0, 0.0), (2.0, 2.0), (0.0, 0.0)]));
+    }
+
+    #[test]
+    fn test_new_wraps_concave_outline() {
+        let poly = Polygon::new(points(&[
+            (0.0, 0.0),
+            (2.0, 0.0),
+            (1.0, 1.0),
+            (2.0, 2.0),
+            (0.0, 2.0),
+        ]))
+        .unwrap();
+
+        assert_eq!(poly.vertices.len(), 4);
+        assert!(find_reflex_vertex(&poly.vertices).is_none());
+    }
+
+    #[test]
+    fn test_new_zero_area() {
+        let res = Polygon::new(points(&[(0.0, 0.0), (1.0, 1.0), (3.0, 3.0), (2.0, 2.0)]));
+
+        assert_eq!(res.err(), Some(ShapeError::ZeroArea));
+    }
+
+    #[test]
+    fn test_from_convex_keeps_vertices() {
+        let verts = points(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]);
+        let poly = Polygon::from_convex(verts.clone()).unwrap();
+
+        assert_eq!(poly.vertices, verts);
+
+        let mut reversed = verts.clone();
+        reversed.reverse();
+        let poly = Polygon::from_convex(reversed).unwrap();
+
+        assert!(signed_area(&poly.vertices) > 0.0);
+    }
+
+    #[test]
+    fn test_from_convex_not_convex() {
+        let res = Polygon::from_convex(points(&[
+            (0.0, 0.0),
+            (2.0, 0.0),
+            (1.0, 1.0),
+            (2.0, 2.0),
+            (0.0, 2.0),
+        ]));
+
+        assert_eq!(res.err(), Some(ShapeError::NotConvex { index: 2 }));
+    }
+
+    #[test]
+    fn test_contains_point() {
+        // A 2 by 1 box turned on its side, covering x from 4.5 to 5.5 and y from -1 to 1
+        let poly = Polygon::new(points(&[
+            (-1.0, -0.5),
+            (1.0, -0.5),
+            (1.0, 0.5),
+            (-1.0, 0.5),
+        ]))
+        .unwrap();
+        let tx = Transform::with_orientation(Point2::new(5.0, 0.0), std::f64::consts::FRAC_PI_2);
+
+        assert!(poly.contains_point(&Point2::new(5.2, 0.9), &tx));
+        assert!(poly.contains_point(&Point2::new(4.5, 0.0), &tx));
+        assert!(!poly.contains_point(&Point2::new(5.9, 0.0), &tx));
+        assert!(!poly.contains_point(&Point2::new(5.0, 1.2), &tx));
+    }
+
+    #[test]
+    fn test_find_support_non_finite_direction() {
+        let poly = Polygon::new(points(&[
+            (-1.0, -1.0),
+            (1.0, -1.0),
+            (1.0, 1.0),
+            (-1.0, 1.0),
+        ]))
+        .unwrap();
+
+        // Callers such as GJK may pass on directions built from NaN positions
+        let support = poly.find_support(&Vector2::new(f64::NAN, 1.0));
+        assert!(poly.vertices.contains(&support));
+        assert_eq!(poly.find_support(&Vector2::new(f64::INFINITY, 0.0)).x, 1.0);
+    }
+
+    #[test]
+    fn test_ray_cast() {
+        // A 2 by 1 box turned on its side, covering x from 4.5 to 5.5 and y from -1 to 1
+        let poly = Polygon::new(points(&[
+            (-1.0, -0.5),
+            (1.0, -0.5),
+            (1.0, 0.5),
+            (-1.0, 0.5),
+        ]))
+        .unwrap();
+        let tx = Transform::with_orientation(Point2::new(5.0, 0.0), std::f64::consts::FRAC_PI_2);
+
+        let ray = Ray::new(Point2::new(0.0, 0.5), Vector2::x(), 10.0).unwrap();
+        let (distance, normal) = poly.ray_cast(&ray, &tx).unwrap();
+        assert!((distance - 4.5).abs() < 1e-12);
+        assert!((normal - Vector2::new(-1.0, 0.0)).norm() < 1e-12);
+
+        let ray = Ray::new(Point2::new(5.2, 3.0), Vector2::new(0.0, -1.0), 10.0).unwrap();
+        let (distance, normal) = poly.ray_cast(&ray, &tx).unwrap();
+        assert!((distance - 2.0).abs() < 1e-12);
+        assert!((normal - Vector2::new(0.0, 1.0)).norm() < 1e-12);
+
+        // Too short, passing by, parallel to an edge outside it and starting inside
+        let short = Ray::new(Point2::new(0.0, 0.5), Vector2::x(), 4.0).unwrap();
+        let past = Ray::new(Point2::new(0.0, 0.0), Vector2::new(1.0, 1.0), 10.0).unwrap();
+        let parallel = Ray::new(Point2::new(4.0, -2.0), Vector2::y(), 10.0).unwrap();
+        let inside = Ray::new(Point2::new(5.0, 0.0), Vector2::x(), 10.0).unwrap();
+        for ray in [short, past, parallel, inside] {
+            assert!(poly.ray_cast(&ray, &tx).is_none());
+        }
+    }
+}
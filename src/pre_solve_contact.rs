@@ -0,0 +1,120 @@
+use nalgebra::Vector2;
+
+use crate::fixture::PartId;
+use crate::manifold::Manifold;
+use crate::scene::BodyHandle;
+use crate::types::{Meter, NormalizedCoefficient};
+
+/// A contact as seen by a pre-solve hook, before the solver responds to it.
+///
+/// The geometry and the bodies involved are read-only, so that the hook cannot leave the manifold
+/// inconsistent with the scene; only how the solver responds can be changed.
+pub struct PreSolveContact<'a> {
+    /// The contact being adjusted.
+    manifold: &'a mut Manifold,
+}
+
+impl<'a> PreSolveContact<'a> {
+    /// Wraps a manifold for a pre-solve hook.
+    pub(crate) fn new(manifold: &'a mut Manifold) -> Self {
+        PreSolveContact { manifold }
+    }
+
+    /// Returns the handle of the first body.
+    pub fn a(&self) -> BodyHandle {
+        self.manifold.a
+    }
+
+    /// Returns the handle of the second body.
+    pub fn b(&self) -> BodyHandle {
+        self.manifold.b
+    }
+
+    /// Returns the part of the first body that is touching.
+    pub fn part_a(&self) -> PartId {
+        self.manifold.part_a
+    }
+
+    /// Returns the part of the second body that is touching.
+    pub fn part_b(&self) -> PartId {
+        self.manifold.part_b
+    }
+
+    /// Returns the contact normal, pointing from `a` to `b`.
+    pub fn normal(&self) -> Vector2<f64> {
+        self.manifold.normal
+    }
+
+    /// Returns how deep the parts overlap.
+    pub fn penetration(&self) -> Meter {
+        self.manifold.penetration
+    }
+
+    /// Returns the contact points in world coordinates.
+    pub fn contacts(&self) -> &[Vector2<f64>] {
+        &self.manifold.contacts[..self.manifold.contact_count]
+    }
+
+    /// Returns `true` if the solver will respond to the contact this step.
+    pub fn is_enabled(&self) -> bool {
+        self.manifold.enabled
+    }
+
+    /// Sets whether the solver responds to the contact this step.
+    ///
+    /// Disabling it lets the bodies pass through each other, as with one-way platforms.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.manifold.enabled = enabled;
+    }
+
+    /// Returns the speed at which the surface of `a` moves under `b`.
+    pub fn tangent_speed(&self) -> f64 {
+        self.manifold.tangent_speed
+    }
+
+    /// Sets the speed at which the surface of `a` moves under `b`, along the normal turned a
+    /// quarter clockwise, as with conveyor belts.
+    pub fn set_tangent_speed(&mut self, speed: f64) {
+        self.manifold.tangent_speed = speed;
+    }
+
+    /// Returns the factor every impulse of the contact is multiplied by.
+    pub fn impulse_scale(&self) -> f64 {
+        self.manifold.impulse_scale
+    }
+
+    /// Sets the factor every impulse of the contact is multiplied by.
+    pub fn set_impulse_scale(&mut self, scale: f64) {
+        self.manifold.impulse_scale = scale;
+    }
+
+    /// Returns the coefficient of restitution used for the contact.
+    pub fn restitution(&self) -> NormalizedCoefficient {
+        self.manifold.restitution()
+    }
+
+    /// Overrides the coefficient of restitution used for the contact this step.
+    pub fn set_restitution(&mut self, restitution: NormalizedCoefficient) {
+        self.manifold.set_restitution(restitution);
+    }
+
+    /// Returns the coefficient of static friction used for the contact.
+    pub fn static_friction(&self) -> NormalizedCoefficient {
+        self.manifold.static_friction()
+    }
+
+    /// Overrides the coefficient of static friction used for the contact this step.
+    pub fn set_static_friction(&mut self, friction: NormalizedCoefficient) {
+        self.manifold.set_static_friction(friction);
+    }
+
+    /// Returns the coefficient of dynamic friction used for the contact.
+    pub fn dynamic_friction(&self) -> NormalizedCoefficient {
+        self.manifold.dynamic_friction()
+    }
+
+    /// Overrides the coefficient of dynamic friction used for the contact this step.
+    pub fn set_dynamic_friction(&mut self, friction: NormalizedCoefficient) {
+        self.manifold.set_dynamic_friction(friction);
+    }
+}
@@ -0,0 +1,96 @@
+use nalgebra::{Point2, Vector2};
+
+use crate::aabb::Aabb;
+use crate::fixture::PartId;
+use crate::scene::BodyHandle;
+
+/// A ray cast into the world, limited to a maximum distance.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray {
+    /// The point the ray starts from.
+    pub origin: Point2<f64>,
+    /// The unit direction the ray travels in.
+    pub dir: Vector2<f64>,
+    /// How far the ray travels.
+    pub max_distance: f64,
+}
+
+impl Ray {
+    /// Creates a new ray.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - The point the ray starts from.
+    /// * `direction` - The direction the ray travels in, which does not need to be normalized.
+    /// * `max_distance` - How far the ray travels.
+    ///
+    /// # Returns
+    ///
+    /// The ray, or `None` if the direction is zero or any value is not finite.
+    pub fn new(origin: Point2<f64>, direction: Vector2<f64>, max_distance: f64) -> Option<Self> {
+        let finite = origin.x.is_finite()
+            && origin.y.is_finite()
+            && direction.x.is_finite()
+            && direction.y.is_finite()
+            && max_distance.is_finite();
+        if !finite || max_distance < 0.0 {
+            return None;
+        }
+
+        let dir = direction.try_normalize(f64::EPSILON)?;
+
+        Some(Ray {
+            origin,
+            dir,
+            max_distance,
+        })
+    }
+
+    /// Returns the point the ray reaches after travelling the given distance.
+    pub fn point_at(&self, distance: f64) -> Point2<f64> {
+        self.origin + self.dir * distance
+    }
+
+    /// Returns the bounding box of the segment the ray covers.
+    pub fn bounding_box(&self) -> Aabb {
+        let end = self.point_at(self.max_distance);
+
+        Aabb::new(self.origin.inf(&end), self.origin.sup(&end))
+    }
+}
+
+/// Where a ray hit a body in a `Scene`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit {
+    /// The body that was hit.
+    pub handle: BodyHandle,
+    /// The convex part of the body that was hit.
+    pub part: PartId,
+    /// The point where the ray entered the body, in world space.
+    pub point: Point2<f64>,
+    /// The outward surface normal at the hit point, in world space.
+    pub normal: Vector2<f64>,
+    /// How far along the ray the hit is, as a fraction of its maximum distance.
+    pub fraction: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let ray = Ray::new(Point2::new(1.0, 1.0), Vector2::new(0.0, -3.0), 2.0).unwrap();
+
+        assert_eq!(ray.dir, Vector2::new(0.0, -1.0));
+        assert_eq!(ray.point_at(2.0), Point2::new(1.0, -1.0));
+        assert_eq!(
+            ray.bounding_box(),
+            Aabb::new(Point2::new(1.0, -1.0), Point2::new(1.0, 1.0))
+        );
+
+        assert!(Ray::new(Point2::origin(), Vector2::zeros(), 1.0).is_none());
+        assert!(Ray::new(Point2::origin(), Vector2::x(), -1.0).is_none());
+        assert!(Ray::new(Point2::origin(), Vector2::new(f64::NAN, 1.0), 1.0).is_none());
+    }
+}
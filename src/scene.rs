@@ -1,23 +1,600 @@
+#[cfg(feature = "render-piston")]
 use graphics::Context;
-use nalgebra::Vector2;
+use nalgebra::{Point2, Vector2};
+#[cfg(feature = "render-piston")]
 use opengl_graphics::GlGraphics;
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::collections::{BTreeMap, BTreeSet};
 
-use crate::constants::{GRAVITY, GREEN, RED};
+use crate::aabb::Aabb;
+use crate::arena::{Arena, Index};
+use crate::broad_phase::BroadPhase;
+#[cfg(feature = "render-piston")]
+use crate::constants::{GREEN, RED};
+use crate::contact_event::{ContactEvent, ContactInfo};
+use crate::fixture::PartId;
+use crate::gjk::Proxy;
+use crate::joint::Joint;
 use crate::manifold::Manifold;
-use crate::object::Object;
+use crate::object::{BodyType, Object};
+use crate::pre_solve_contact::PreSolveContact;
+use crate::ray::{Ray, RayHit};
+use crate::sensor::{SensorEvent, SensorOverlap};
+use crate::shape_cast::{shape_cast, ShapeCastHit};
+use crate::shapes::{Shape, Shapes};
+use crate::transform::Transform;
+use crate::tree_broad_phase::TreeBroadPhase;
+use crate::types::MeterPerSquaredSecond;
+use crate::world_settings::WorldSettings;
+
+/// A stable handle to a body in a `Scene`.
+///
+/// Handles stay valid until the body is removed and never resolve to a different body afterwards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BodyHandle(Index);
+
+impl BodyHandle {
+    /// Creates a handle that refers to no body, for tests that only need distinct handles.
+    #[cfg(test)]
+    pub(crate) fn from_slot(slot: u32) -> Self {
+        BodyHandle(Index::from_raw(slot, 0))
+    }
+}
+
+/// A stable handle to a joint in a `Scene`.
+///
+/// Handles stay valid until the joint is removed and never resolve to a different joint
+/// afterwards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JointHandle(Index);
+
+/// Decides whether two bodies should collide, on top of their fixtures' collision filters.
+type ContactFilter = Box<dyn FnMut(BodyHandle, &Object, BodyHandle, &Object) -> bool + Send>;
+
+/// Adjusts a contact between the narrow phase and the solver.
+type PreSolveHook = Box<dyn FnMut(&mut PreSolveContact, &Object, &Object) + Send>;
 
 /// Represents a physics scene with a collection of objects and contact manifolds.
 pub struct Scene {
-    /// A collection of objects in the scene, each wrapped in a `RefCell` and reference-counted `Rc`.
-    pub objects: Vec<Rc<RefCell<Object>>>,
+    /// The objects in the scene, addressed through `BodyHandle`s.
+    bodies: Arena<Object>,
+    /// The joints between bodies, addressed through `JointHandle`s.
+    joints: Arena<Joint>,
+    /// The strategy used to find candidate pairs for the narrow phase.
+    broad_phase: Box<dyn BroadPhase>,
+    /// Whether bodies may have been moved since the broad phase was last updated.
+    broad_phase_stale: bool,
+    /// The user callback that can veto collisions between bodies.
+    contact_filter: Option<ContactFilter>,
+    /// The user callback that may change contacts before they are solved.
+    pre_solve_hook: Option<PreSolveHook>,
+    /// The parts that overlapped sensors in the last step.
+    sensor_overlaps: BTreeSet<SensorOverlap>,
+    /// Sensor events not yet drained.
+    sensor_events: Vec<SensorEvent>,
+    /// The contacts between solid parts at the end of the last step.
+    touching: BTreeMap<(BodyHandle, PartId, BodyHandle, PartId), ContactInfo>,
+    /// Contact events not yet drained.
+    contact_events: Vec<ContactEvent>,
     /// A collection of contact manifolds representing interactions between objects.
     pub contacts: Vec<Manifold>,
+    /// The tunable parameters used by `step`, which may be changed between steps.
+    pub settings: WorldSettings,
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Scene {
-    // Adapted from https://code.tutsplus.com/series/how-to-create-a-custom-physics-engine--gamedev-12715
+    /// Creates a new, empty scene with the default settings.
+    pub fn new() -> Self {
+        Self::with_settings(WorldSettings::default())
+    }
+
+    /// Creates a new, empty scene with the given settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - The world settings of the scene.
+    pub fn with_settings(settings: WorldSettings) -> Self {
+        Scene {
+            bodies: Arena::new(),
+            joints: Arena::new(),
+            broad_phase: Box::new(TreeBroadPhase::default()),
+            broad_phase_stale: false,
+            contact_filter: None,
+            pre_solve_hook: None,
+            sensor_overlaps: BTreeSet::new(),
+            sensor_events: Vec::new(),
+            touching: BTreeMap::new(),
+            contact_events: Vec::new(),
+            contacts: Vec::new(),
+            settings,
+        }
+    }
+
+    /// Adds a body to the scene.
+    ///
+    /// # Arguments
+    ///
+    /// * `body` - The object to add.
+    ///
+    /// # Returns
+    ///
+    /// The handle used to refer to the body from now on.
+    pub fn add_body(&mut self, body: Object) -> BodyHandle {
+        let aabb = body.bounding_box();
+        let handle = BodyHandle(self.bodies.insert(body));
+        self.broad_phase.insert(handle, aabb);
+
+        handle
+    }
+
+    /// Removes a body from the scene, along with any contacts and joints it is part of.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the body to remove.
+    ///
+    /// # Returns
+    ///
+    /// The removed object, or `None` if the handle is stale.
+    pub fn remove_body(&mut self, handle: BodyHandle) -> Option<Object> {
+        let body = self.bodies.remove(handle.0)?;
+        self.contacts.retain(|m| m.a != handle && m.b != handle);
+        let attached: Vec<Index> = self
+            .joints
+            .iter()
+            .filter(|(_, joint)| {
+                let (a, b) = joint.bodies();
+                a == handle || b == handle
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+        for idx in attached {
+            self.joints.remove(idx);
+        }
+
+        self.broad_phase.remove(handle);
+
+        Some(body)
+    }
+
+    /// Returns a reference to a body, or `None` if the handle is stale.
+    pub fn body(&self, handle: BodyHandle) -> Option<&Object> {
+        self.bodies.get(handle.0)
+    }
+
+    /// Returns a mutable reference to a body, or `None` if the handle is stale.
+    pub fn body_mut(&mut self, handle: BodyHandle) -> Option<&mut Object> {
+        self.broad_phase_stale = true;
+        self.bodies.get_mut(handle.0)
+    }
+
+    /// Returns mutable references to two distinct bodies at once.
+    ///
+    /// # Returns
+    ///
+    /// `None` if either handle is stale or both handles are the same.
+    pub fn body_pair_mut(
+        &mut self,
+        a: BodyHandle,
+        b: BodyHandle,
+    ) -> Option<(&mut Object, &mut Object)> {
+        self.broad_phase_stale = true;
+        self.bodies.get2_mut(a.0, b.0)
+    }
+
+    /// Iterates over all bodies in the scene along with their handles.
+    pub fn bodies(&self) -> impl Iterator<Item = (BodyHandle, &Object)> {
+        self.bodies
+            .iter()
+            .map(|(idx, body)| (BodyHandle(idx), body))
+    }
+
+    /// Iterates mutably over all bodies in the scene along with their handles.
+    pub fn bodies_mut(&mut self) -> impl Iterator<Item = (BodyHandle, &mut Object)> {
+        self.broad_phase_stale = true;
+        self.bodies
+            .iter_mut()
+            .map(|(idx, body)| (BodyHandle(idx), body))
+    }
+
+    /// Returns the number of bodies in the scene.
+    pub fn body_count(&self) -> usize {
+        self.bodies.len()
+    }
+
+    /// Adds a joint between two bodies of the scene.
+    ///
+    /// # Arguments
+    ///
+    /// * `joint` - The joint to add, such as a `DistanceJoint`.
+    ///
+    /// # Returns
+    ///
+    /// The handle used to refer to the joint from now on, or `None` if either body handle is
+    /// stale or both are the same.
+    pub fn add_joint(&mut self, joint: impl Into<Joint>) -> Option<JointHandle> {
+        let joint = joint.into();
+        let (a, b) = joint.bodies();
+        if a == b || !self.bodies.contains(a.0) || !self.bodies.contains(b.0) {
+            return None;
+        }
+
+        Some(JointHandle(self.joints.insert(joint)))
+    }
+
+    /// Removes a joint from the scene.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the joint to remove.
+    ///
+    /// # Returns
+    ///
+    /// The removed joint, or `None` if the handle is stale.
+    pub fn remove_joint(&mut self, handle: JointHandle) -> Option<Joint> {
+        self.joints.remove(handle.0)
+    }
+
+    /// Returns a reference to a joint, or `None` if the handle is stale.
+    pub fn joint(&self, handle: JointHandle) -> Option<&Joint> {
+        self.joints.get(handle.0)
+    }
+
+    /// Returns a mutable reference to a joint, or `None` if the handle is stale.
+    pub fn joint_mut(&mut self, handle: JointHandle) -> Option<&mut Joint> {
+        self.joints.get_mut(handle.0)
+    }
+
+    /// Iterates over all joints in the scene along with their handles.
+    pub fn joints(&self) -> impl Iterator<Item = (JointHandle, &Joint)> {
+        self.joints
+            .iter()
+            .map(|(idx, joint)| (JointHandle(idx), joint))
+    }
+
+    /// Replaces the broad phase strategy, handing all current bodies over to the new one.
+    ///
+    /// # Arguments
+    ///
+    /// * `broad_phase` - The new strategy, such as a `TreeBroadPhase` (the default), a
+    ///   `SweepAndPrune`, a `SpatialHashGrid` or the `BruteForce` reference implementation.
+    pub fn set_broad_phase(&mut self, mut broad_phase: Box<dyn BroadPhase>) {
+        for (idx, body) in self.bodies.iter() {
+            broad_phase.insert(BodyHandle(idx), body.bounding_box());
+        }
+
+        self.broad_phase = broad_phase;
+    }
+
+    /// Sets a callback that decides whether two bodies should collide.
+    ///
+    /// It is called during `step` for every pair the broad phase finds, after pairs with neither a
+    /// dynamic body nor a sensor are skipped. Returning `false` lets the bodies pass through each
+    /// other for that step. Fixture collision filters still apply to pairs the callback lets
+    /// through.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The callback, given the handle and body of each side of the pair.
+    pub fn set_contact_filter<F>(&mut self, filter: F)
+    where
+        F: FnMut(BodyHandle, &Object, BodyHandle, &Object) -> bool + Send + 'static,
+    {
+        self.contact_filter = Some(Box::new(filter));
+    }
+
+    /// Removes the callback set with `set_contact_filter`, so that only fixture collision
+    /// filters decide which bodies collide.
+    pub fn clear_contact_filter(&mut self) {
+        self.contact_filter = None;
+    }
+
+    /// Sets a callback that may change each contact after the narrow phase and before the
+    /// solver.
+    ///
+    /// It is called during `step` for every contact between solid parts, whether the parts just
+    /// started touching or already touched in the previous step. The contact's materials have
+    /// been mixed by then, so the callback can disable it, override its restitution and
+    /// friction, set a surface speed or scale its impulses. Disabled contacts still produce
+    /// contact events, with no impulses.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - The callback, given the contact along with its two bodies.
+    pub fn set_pre_solve_hook<F>(&mut self, hook: F)
+    where
+        F: FnMut(&mut PreSolveContact, &Object, &Object) + Send + 'static,
+    {
+        self.pre_solve_hook = Some(Box::new(hook));
+    }
+
+    /// Removes the callback set with `set_pre_solve_hook`.
+    pub fn clear_pre_solve_hook(&mut self) {
+        self.pre_solve_hook = None;
+    }
+
+    /// Updates the broad phase and returns the candidate pairs of bodies it finds.
+    ///
+    /// Only these pairs are passed on to the narrow phase by `step`.
+    ///
+    /// # Returns
+    ///
+    /// The candidate pairs, each with the lower handle first and the whole list sorted.
+    pub fn candidate_pairs(&mut self) -> Vec<(BodyHandle, BodyHandle)> {
+        if self.broad_phase_stale {
+            self.update_broad_phase();
+        }
+
+        self.broad_phase.pairs()
+    }
+
+    /// Hands the current bounding box of every body to the broad phase.
+    fn update_broad_phase(&mut self) {
+        for (idx, body) in self.bodies.iter() {
+            self.broad_phase
+                .update(BodyHandle(idx), body.bounding_box());
+        }
+
+        self.broad_phase_stale = false;
+    }
+
+    /// Returns the bodies that might overlap a box.
+    ///
+    /// The broad phase is used unless bodies were changed through `body_mut` or similar since the
+    /// last step, in which case every body's bounding box is tested instead.
+    fn query_candidates(&self, aabb: &Aabb) -> Vec<BodyHandle> {
+        if self.broad_phase_stale {
+            return self
+                .bodies()
+                .filter(|(_, body)| body.bounding_box().overlaps(aabb))
+                .map(|(handle, _)| handle)
+                .collect();
+        }
+
+        self.broad_phase.query(aabb)
+    }
+
+    /// Finds the bodies whose shape contains a point, such as the one under the mouse cursor.
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - The point, in world space.
+    ///
+    /// # Returns
+    ///
+    /// The handles of the bodies, in handle order.
+    pub fn query_point(&self, p: Point2<f64>) -> Vec<BodyHandle> {
+        let mut handles: Vec<BodyHandle> = self
+            .query_candidates(&Aabb::new(p, p))
+            .into_iter()
+            .filter(|handle| {
+                let body = &self.bodies[handle.0];
+                body.parts().any(|part| {
+                    let (shape, tx) = body.part(part);
+                    shape.contains_point(&p, &tx)
+                })
+            })
+            .collect();
+
+        handles.sort();
+        handles
+    }
+
+    /// Finds the bodies whose bounding box overlaps a box, such as a spawn area.
+    ///
+    /// Only the bounding boxes are compared, so a body may be reported even though its shape
+    /// does not reach into the box.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - The corner of the box with the smallest coordinates, in world space.
+    /// * `max` - The corner of the box with the largest coordinates, in world space.
+    ///
+    /// # Returns
+    ///
+    /// The handles of the bodies, in handle order.
+    pub fn query_aabb(&self, min: Point2<f64>, max: Point2<f64>) -> Vec<BodyHandle> {
+        let aabb = Aabb::new(min, max);
+
+        let mut handles: Vec<BodyHandle> = self
+            .query_candidates(&aabb)
+            .into_iter()
+            .filter(|handle| self.bodies[handle.0].bounding_box().overlaps(&aabb))
+            .collect();
+
+        handles.sort();
+        handles
+    }
+
+    /// Casts a ray into the scene and finds the closest body it hits.
+    ///
+    /// Rays starting inside a body do not hit that body.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - The point the ray starts from.
+    /// * `direction` - The direction the ray travels in, which does not need to be normalized.
+    /// * `max_distance` - How far the ray travels.
+    /// * `filter` - Called with every body the ray might hit; only bodies it returns `true` for
+    ///   are tested.
+    ///
+    /// # Returns
+    ///
+    /// The closest hit, or `None` if the ray hits nothing or the direction is zero.
+    pub fn ray_cast<F>(
+        &self,
+        origin: Point2<f64>,
+        direction: Vector2<f64>,
+        max_distance: f64,
+        filter: F,
+    ) -> Option<RayHit>
+    where
+        F: FnMut(BodyHandle, &Object) -> bool,
+    {
+        let ray = Ray::new(origin, direction, max_distance)?;
+
+        self.ray_hits(&ray, filter)
+            .min_by(|a, b| a.fraction.total_cmp(&b.fraction))
+    }
+
+    /// Casts a ray into the scene and finds every body it hits.
+    ///
+    /// Each body is reported once, where the ray first enters it.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - The point the ray starts from.
+    /// * `direction` - The direction the ray travels in, which does not need to be normalized.
+    /// * `max_distance` - How far the ray travels.
+    /// * `filter` - Called with every body the ray might hit; only bodies it returns `true` for
+    ///   are tested.
+    ///
+    /// # Returns
+    ///
+    /// The hits, ordered from closest to furthest.
+    pub fn ray_cast_all<F>(
+        &self,
+        origin: Point2<f64>,
+        direction: Vector2<f64>,
+        max_distance: f64,
+        filter: F,
+    ) -> Vec<RayHit>
+    where
+        F: FnMut(BodyHandle, &Object) -> bool,
+    {
+        let Some(ray) = Ray::new(origin, direction, max_distance) else {
+            return Vec::new();
+        };
+
+        let mut hits: Vec<RayHit> = self.ray_hits(&ray, filter).collect();
+        hits.sort_by(|a, b| a.fraction.total_cmp(&b.fraction));
+
+        hits
+    }
+
+    /// Sweeps a shape along a translation and finds the first body it touches, such as the
+    /// ground under a character.
+    ///
+    /// Bodies the shape already touches at the start are not reported, like rays starting inside a
+    /// body.
+    ///
+    /// # Arguments
+    ///
+    /// * `shape` - The shape to sweep, which does not need to belong to a body in the scene.
+    /// * `transform` - The placement of the shape at the start of the sweep.
+    /// * `translation` - How far the shape moves.
+    /// * `filter` - Called with every body the shape might touch; only bodies it returns `true`
+    ///   for are tested.
+    ///
+    /// # Returns
+    ///
+    /// The first hit, or `None` if the shape can move all the way.
+    pub fn shape_cast<F>(
+        &self,
+        shape: &Shapes,
+        transform: Transform,
+        translation: Vector2<f64>,
+        mut filter: F,
+    ) -> Option<ShapeCastHit>
+    where
+        F: FnMut(BodyHandle, &Object) -> bool,
+    {
+        let end = Transform {
+            pos: transform.pos + translation,
+            ..transform
+        };
+        let swept = shape
+            .bounding_box(&transform)
+            .union(&shape.bounding_box(&end));
+
+        let mut closest: Option<ShapeCastHit> = None;
+        for handle in self.query_candidates(&swept) {
+            let body = &self.bodies[handle.0];
+            if !filter(handle, body) {
+                continue;
+            }
+
+            for part in body.parts() {
+                let (part_a, tx) = body.part(part);
+                let a = Proxy { part: part_a, tx };
+
+                for idx in 0..shape.part_count() {
+                    let b = Proxy {
+                        part: shape.part(idx),
+                        tx: transform,
+                    };
+
+                    let Some((toi, point, normal)) = shape_cast(&a, &b, &translation) else {
+                        continue;
+                    };
+
+                    if closest.is_none_or(|hit| toi < hit.toi) {
+                        closest = Some(ShapeCastHit {
+                            handle,
+                            part,
+                            toi,
+                            point,
+                            normal,
+                        });
+                    }
+                }
+            }
+        }
+
+        closest
+    }
+
+    /// Finds where a ray first enters each body it hits, in no particular order.
+    fn ray_hits<'a, F>(&'a self, ray: &'a Ray, mut filter: F) -> impl Iterator<Item = RayHit> + 'a
+    where
+        F: FnMut(BodyHandle, &Object) -> bool + 'a,
+    {
+        self.query_candidates(&ray.bounding_box())
+            .into_iter()
+            .filter_map(move |handle| {
+                let body = &self.bodies[handle.0];
+                if !filter(handle, body) {
+                    return None;
+                }
+
+                // A ray starting in one part of a compound body would otherwise hit its others
+                if body.parts().any(|part| {
+                    let (shape, tx) = body.part(part);
+                    shape.contains_point(&ray.origin, &tx)
+                }) {
+                    return None;
+                }
+
+                // The closest part is where the ray enters the body
+                body.parts()
+                    .filter_map(|part| {
+                        let (shape, tx) = body.part(part);
+                        shape
+                            .ray_cast(ray, &tx)
+                            .map(|(distance, normal)| (part, distance, normal))
+                    })
+                    .min_by(|a, b| a.1.total_cmp(&b.1))
+                    .map(|(part, distance, normal)| RayHit {
+                        handle,
+                        part,
+                        point: ray.point_at(distance),
+                        normal,
+                        fraction: if ray.max_distance > 0.0 {
+                            distance / ray.max_distance
+                        } else {
+                            0.0
+                        },
+                    })
+            })
+    }
+
+    // Adapted from
+    // https://code.tutsplus.com/series/how-to-create-a-custom-physics-engine--gamedev-12715
     /// Advances the simulation by a specified time step.
     ///
     /// # Arguments
@@ -26,49 +603,239 @@ impl Scene {
     pub fn step(&mut self, dt: f64) {
         self.contacts.clear();
 
-        for (i, a) in self.objects.iter().enumerate() {
-            for b in self.objects.iter().skip(i + 1) {
-                if a.borrow().mass_data.mass.is_infinite()
-                    && b.borrow().mass_data.mass.is_infinite()
-                {
+        // A joint replaced through `joint_mut` may refer to bodies that are gone, so it is dropped
+        // as `add_joint` would have refused it
+        let stale: Vec<Index> = self
+            .joints
+            .iter()
+            .filter(|(_, joint)| {
+                let (a, b) = joint.bodies();
+                a == b || !self.bodies.contains(a.0) || !self.bodies.contains(b.0)
+            })
+            .map(|(index, _)| index)
+            .collect();
+        for index in stale {
+            self.joints.remove(index);
+        }
+
+        // Candidate pairs come ordered by handle, so the joined pairs are stored the same way
+        let joined: BTreeSet<(BodyHandle, BodyHandle)> = self
+            .joints
+            .iter()
+            .filter(|(_, joint)| !joint.collide_connected())
+            .map(|(_, joint)| {
+                let (a, b) = joint.bodies();
+                (a.min(b), a.max(b))
+            })
+            .collect();
+
+        for (ha, hb) in self.candidate_pairs() {
+            let a = &self.bodies[ha.0];
+            let b = &self.bodies[hb.0];
+
+            // Only pairs with at least one dynamic body can respond to a collision, while a
+            // sensor detects bodies of any type
+            let can_respond = a.is_dynamic() || b.is_dynamic();
+            let has_sensor = a.fixtures.iter().chain(&b.fixtures).any(|f| f.is_sensor);
+            if !can_respond && !has_sensor {
+                continue;
+            }
+
+            if joined.contains(&(ha, hb)) {
+                continue;
+            }
+
+            if let Some(filter) = &mut self.contact_filter {
+                if !filter(ha, a, hb, b) {
                     continue;
                 }
+            }
+
+            // Every pair of convex parts gets its own manifold, while the impulses all go to the
+            // two bodies
+            for part_a in a.parts() {
+                for part_b in b.parts() {
+                    let fixture_a = &a.fixtures[part_a.fixture];
+                    let fixture_b = &b.fixtures[part_b.fixture];
+                    if !fixture_a.filter.should_collide(&fixture_b.filter) {
+                        continue;
+                    }
 
-                let mut m = Manifold::new(Rc::clone(a), Rc::clone(b));
-                m.solve();
+                    // Sensors only detect solid parts
+                    if fixture_a.is_sensor && fixture_b.is_sensor {
+                        continue;
+                    }
 
-                if m.contact_count > 0 {
-                    self.contacts.push(m);
+                    let is_sensor = fixture_a.is_sensor || fixture_b.is_sensor;
+                    if !is_sensor && !can_respond {
+                        continue;
+                    }
+
+                    let mut m = Manifold::with_parts(ha, hb, part_a, part_b);
+                    m.is_sensor = is_sensor;
+                    m.solve(a, b);
+
+                    if m.contact_count > 0 {
+                        m.mix_materials(a, b);
+                        self.contacts.push(m);
+                    }
                 }
             }
         }
 
-        for obj in &self.objects {
-            integrate_forces(&mut obj.borrow_mut(), dt);
+        self.update_sensor_overlaps();
+
+        if let Some(hook) = &mut self.pre_solve_hook {
+            for contact in self.contacts.iter_mut().filter(|m| !m.is_sensor) {
+                let a = &self.bodies[contact.a.0];
+                let b = &self.bodies[contact.b.0];
+                hook(&mut PreSolveContact::new(contact), a, b);
+            }
+        }
+
+        for (_, obj) in self.bodies.iter_mut() {
+            integrate_forces(obj, self.settings.gravity, dt);
         }
 
         for contact in &mut self.contacts {
-            contact.initialize(dt);
+            let a = &self.bodies[contact.a.0];
+            let b = &self.bodies[contact.b.0];
+            contact.initialize(a, b, &self.settings, dt);
+        }
+
+        for (_, joint) in self.joints.iter_mut() {
+            let (ha, hb) = joint.bodies();
+            if let (Some(a), Some(b)) = (self.bodies.get(ha.0), self.bodies.get(hb.0)) {
+                joint.initialize(a, b, dt);
+            }
         }
 
-        for _ in 0..10 {
+        for _ in 0..self.settings.velocity_iterations {
+            for (_, joint) in self.joints.iter_mut() {
+                let (ha, hb) = joint.bodies();
+                if let Some((a, b)) = self.bodies.get2_mut(ha.0, hb.0) {
+                    joint.apply_impulse(a, b, dt);
+                }
+            }
+
             for contact in &mut self.contacts {
-                contact.apply_impulse();
+                if let Some((a, b)) = self.bodies.get2_mut(contact.a.0, contact.b.0) {
+                    contact.apply_impulse(a, b);
+                }
             }
         }
 
-        for obj in &self.objects {
-            integrate_velocities(&mut obj.borrow_mut(), dt);
+        for (_, obj) in self.bodies.iter_mut() {
+            integrate_velocities(obj, self.settings.gravity, dt);
         }
 
         for contact in &mut self.contacts {
-            contact.positional_correction();
+            if let Some((a, b)) = self.bodies.get2_mut(contact.a.0, contact.b.0) {
+                contact.positional_correction(a, b, &self.settings);
+            }
+        }
+
+        for (_, joint) in self.joints.iter_mut() {
+            let (ha, hb) = joint.bodies();
+            if let Some((a, b)) = self.bodies.get2_mut(ha.0, hb.0) {
+                joint.positional_correction(a, b);
+            }
+        }
+
+        for (_, obj) in self.bodies.iter_mut() {
+            obj.force = Vector2::zeros();
+            obj.kinematics.torque = 0.0;
         }
 
-        for obj in &mut self.objects {
-            obj.borrow_mut().force = Vector2::zeros();
-            obj.borrow_mut().kinematics.torque = 0.0;
+        self.update_touching();
+
+        // Keep the broad phase current so that queries between steps can use it
+        self.update_broad_phase();
+    }
+
+    /// Records which parts overlap sensors after the narrow phase, along with the changes since
+    /// the previous step as events.
+    fn update_sensor_overlaps(&mut self) {
+        let overlaps: BTreeSet<SensorOverlap> = self
+            .contacts
+            .iter()
+            .filter(|m| m.is_sensor)
+            .map(|m| {
+                let a = &self.bodies[m.a.0];
+                if a.fixtures[m.part_a.fixture].is_sensor {
+                    SensorOverlap {
+                        sensor: m.a,
+                        sensor_part: m.part_a,
+                        visitor: m.b,
+                        visitor_part: m.part_b,
+                    }
+                } else {
+                    SensorOverlap {
+                        sensor: m.b,
+                        sensor_part: m.part_b,
+                        visitor: m.a,
+                        visitor_part: m.part_a,
+                    }
+                }
+            })
+            .collect();
+
+        let ended = self.sensor_overlaps.difference(&overlaps);
+        self.sensor_events
+            .extend(ended.map(|overlap| SensorEvent::End(*overlap)));
+        let begun = overlaps.difference(&self.sensor_overlaps);
+        self.sensor_events
+            .extend(begun.map(|overlap| SensorEvent::Begin(*overlap)));
+
+        self.sensor_overlaps = overlaps;
+    }
+
+    /// Records which solid parts touch after the solver has run, along with the changes since
+    /// the previous step as events.
+    fn update_touching(&mut self) {
+        let touching: BTreeMap<_, _> = self
+            .contacts
+            .iter()
+            .filter(|m| !m.is_sensor)
+            .map(|m| ((m.a, m.part_a, m.b, m.part_b), ContactInfo::from(m)))
+            .collect();
+
+        for (key, info) in &self.touching {
+            if !touching.contains_key(key) {
+                self.contact_events.push(ContactEvent::End(*info));
+            }
+        }
+        for (key, info) in &touching {
+            self.contact_events
+                .push(if self.touching.contains_key(key) {
+                    ContactEvent::Persist(*info)
+                } else {
+                    ContactEvent::Begin(*info)
+                });
         }
+
+        self.touching = touching;
+    }
+
+    /// Removes and returns the contact events recorded since the last call, oldest first.
+    ///
+    /// Every step records an event for each pair of touching parts, so drain the events after
+    /// every step to keep them from piling up.
+    pub fn drain_contact_events(&mut self) -> impl Iterator<Item = ContactEvent> + '_ {
+        self.contact_events.drain(..)
+    }
+
+    /// Returns the parts overlapping sensors as of the last step, sorted.
+    pub fn sensor_overlaps(&self) -> impl Iterator<Item = &SensorOverlap> {
+        self.sensor_overlaps.iter()
+    }
+
+    /// Removes and returns the sensor events recorded since the last call, oldest first.
+    ///
+    /// Events keep piling up until they are drained, so call this after every `step` that the
+    /// events matter for.
+    pub fn drain_sensor_events(&mut self) -> impl Iterator<Item = SensorEvent> + '_ {
+        self.sensor_events.drain(..)
     }
 
     /// Renders the scene, including objects and visualizations for contact points and normals.
@@ -77,9 +844,10 @@ impl Scene {
     ///
     /// * `c` - The graphics context.
     /// * `gl` - The OpenGL graphics object.
+    #[cfg(feature = "render-piston")]
     pub fn render(&self, c: Context, gl: &mut GlGraphics) {
-        for object in &self.objects {
-            object.borrow().draw(c, gl);
+        for (_, object) in self.bodies() {
+            object.draw(c, gl);
         }
 
         // Visualize contact points as red points
@@ -111,37 +879,41 @@ impl Scene {
     }
 }
 
-// Adapted from https://code.tutsplus.com/series/how-to-create-a-custom-physics-engine--gamedev-12715
+// Adapted from
+// https://code.tutsplus.com/series/how-to-create-a-custom-physics-engine--gamedev-12715
 /// Semi-implicit Euler method for integrating forces over time.
 ///
 /// # Arguments
 ///
 /// * `obj` - The object to integrate forces for.
+/// * `gravity` - The gravitational acceleration of the scene.
 /// * `dt` - The time step.
-fn integrate_forces(obj: &mut Object, dt: f64) {
-    if obj.mass_data.mass.is_infinite() {
+fn integrate_forces(obj: &mut Object, gravity: Vector2<MeterPerSquaredSecond>, dt: f64) {
+    if !obj.is_dynamic() {
         return;
     }
 
-    obj.kinematics.vel += (obj.force * obj.mass_data.inv_mass + GRAVITY) * (dt / 2.0);
+    obj.kinematics.vel += (obj.force * obj.mass_data.inv_mass + gravity) * (dt / 2.0);
     obj.kinematics.angular_vel += obj.kinematics.torque * obj.mass_data.inv_m_inertia * (dt / 2.0);
 }
 
-// Adapted from https://code.tutsplus.com/series/how-to-create-a-custom-physics-engine--gamedev-12715
+// Adapted from
+// https://code.tutsplus.com/series/how-to-create-a-custom-physics-engine--gamedev-12715
 /// Semi-implicit Euler method for integrating velocities over time.
 ///
 /// # Arguments
 ///
 /// * `obj` - The object to integrate velocities for.
+/// * `gravity` - The gravitational acceleration of the scene.
 /// * `dt` - The time step.
-fn integrate_velocities(obj: &mut Object, dt: f64) {
-    if obj.mass_data.mass.is_infinite() {
+fn integrate_velocities(obj: &mut Object, gravity: Vector2<MeterPerSquaredSecond>, dt: f64) {
+    if obj.body_type == BodyType::Static {
         return;
     }
 
     obj.tx.pos += obj.kinematics.vel * dt;
-    obj.tx.orientation += obj.kinematics.angular_vel * dt;
-    integrate_forces(obj, dt);
+    obj.tx.rotate(obj.kinematics.angular_vel * dt);
+    integrate_forces(obj, gravity, dt);
 }
 
 #[cfg(test)]
@@ -150,8 +922,28 @@ mod tests {
 
     use nalgebra::Point2;
     use ordered_float::OrderedFloat;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
 
-    use crate::{circle::Circle, shapes::Shapes, transform::Transform};
+    use crate::{
+        aabb::Aabb,
+        brute_force::BruteForce,
+        circle::Circle,
+        collision_filter::CollisionFilter,
+        concave_polygon::ConcavePolygon,
+        distance_joint::DistanceJoint,
+        fixture::{Fixture, PartId},
+        kinematics::Kinematics,
+        mass_data::MassData,
+        material::Material,
+        object_builder::ObjectBuilder,
+        polygon::Polygon,
+        shapes::{ShapeDiscriminant, Shapes},
+        spatial_hash_grid::SpatialHashGrid,
+        support_map::SupportMap,
+        sweep_and_prune::{Axis, SweepAndPrune},
+        transform::Transform,
+    };
 
     #[test]
     fn test_integrate_forces() {
@@ -159,19 +951,24 @@ mod tests {
             radius: OrderedFloat(1.0),
         });
         let tx = Transform::new(Point2::new(0.0, 0.0));
-        let mut a = Object::new(circle, tx, None, None, None);
-        let initial_vel = a.kinematics.vel.clone();
-        let initial_angular_vel = a.kinematics.angular_vel;
+        let mut a = Object::builder(circle).transform(tx).build();
+        let gravity = WorldSettings::default().gravity;
+        let inv_mass = a.mass_data.inv_mass;
+        let inv_m_inertia = a.mass_data.inv_m_inertia;
 
         a.force = Vector2::new(1.0, 2.0);
         a.kinematics.torque = 3.0;
 
         let dt = 0.1;
 
-        integrate_forces(&mut a, dt);
+        integrate_forces(&mut a, gravity, dt);
 
-        assert_ne!(a.kinematics.vel, initial_vel);
-        assert_ne!(a.kinematics.angular_vel, initial_angular_vel);
+        // Objects start at rest, so only half a step of acceleration is applied
+        assert_eq!(
+            a.kinematics.vel,
+            (Vector2::new(1.0, 2.0) * inv_mass + gravity) * (dt / 2.0)
+        );
+        assert_eq!(a.kinematics.angular_vel, 3.0 * inv_m_inertia * (dt / 2.0));
     }
 
     #[test]
@@ -180,9 +977,7 @@ mod tests {
             radius: OrderedFloat(1.0),
         });
         let tx = Transform::new(Point2::new(0.0, 0.0));
-        let mut obj = Object::new(circle, tx, None, None, None);
-        let initial_pos = obj.tx.pos;
-        let initial_orientation = obj.tx.orientation;
+        let mut obj = Object::builder(circle).transform(tx).build();
 
         obj.kinematics.vel = Vector2::new(1.0, 2.0);
         obj.kinematics.angular_vel = 3.0;
@@ -191,9 +986,1451 @@ mod tests {
 
         let dt = 0.1;
 
-        integrate_velocities(&mut obj, dt);
+        integrate_velocities(&mut obj, WorldSettings::default().gravity, dt);
+
+        assert!((obj.tx.pos - Point2::new(0.1, 0.2)).norm() < 1e-12);
+        assert!((obj.tx.orientation() - 0.3).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_spinning_square_lands_on_corner() {
+        let floor = Object::builder(Shapes::Polygon(
+            Polygon::new(vec![
+                Point2::new(-10.0, -1.0),
+                Point2::new(10.0, -1.0),
+                Point2::new(10.0, 1.0),
+                Point2::new(-10.0, 1.0),
+            ])
+            .unwrap(),
+        ))
+        .transform(Transform::new(Point2::new(0.0, 1.0)))
+        .material(Material::new(
+            1.0,
+            OrderedFloat(0.0),
+            OrderedFloat(0.0),
+            OrderedFloat(0.0),
+        ))
+        .kinematics(Kinematics::new(Vector2::zeros(), 0.0, 0.0))
+        .body_type(BodyType::Static)
+        .build();
+        let square = Object::builder(Shapes::Polygon(
+            Polygon::new(vec![
+                Point2::new(-1.0, -1.0),
+                Point2::new(1.0, -1.0),
+                Point2::new(1.0, 1.0),
+                Point2::new(-1.0, 1.0),
+            ])
+            .unwrap(),
+        ))
+        .transform(Transform::with_orientation(
+            Point2::new(0.0, -2.0),
+            std::f64::consts::FRAC_PI_4,
+        ))
+        .material(Material::new(
+            1.0,
+            OrderedFloat(0.5),
+            OrderedFloat(0.0),
+            OrderedFloat(0.0),
+        ))
+        .kinematics(Kinematics::new(Vector2::new(0.0, 5.0), 0.1, 0.0))
+        .build();
+
+        let mut scene = Scene::new();
+        scene.add_body(floor);
+        let square = scene.add_body(square);
+
+        let dt = 1.0 / 60.0;
+        let mut steps = 0;
+        while scene.contacts.is_empty() {
+            assert!(steps < 120, "the square never reached the floor");
+            scene.step(dt);
+            steps += 1;
+        }
+
+        // The lowest corner of the rotated square is the only point touching the floor
+        let manifold = &scene.contacts[0];
+        assert_eq!(manifold.contact_count, 1);
+
+        let sq = scene.body(square).unwrap();
+        assert!(sq.tx.orientation() > std::f64::consts::FRAC_PI_4);
+        let corner = [
+            Point2::new(-1.0, -1.0),
+            Point2::new(1.0, -1.0),
+            Point2::new(1.0, 1.0),
+            Point2::new(-1.0, 1.0),
+        ]
+        .iter()
+        .map(|v| sq.tx.to_world(v))
+        .max_by(|a, b| a.y.partial_cmp(&b.y).unwrap())
+        .unwrap();
+        assert!((manifold.contacts[0] - corner.coords).norm() < 0.2);
+
+        // The off-center impact must have changed the spin
+        assert!((sq.kinematics.angular_vel - 0.1).abs() > 1e-3);
+    }
+
+    #[test]
+    fn test_remove_body() {
+        let circle = |x| {
+            Object::builder(Shapes::Circle(Circle {
+                radius: OrderedFloat(1.0),
+            }))
+            .transform(Transform::new(Point2::new(x, 0.0)))
+            .kinematics(Kinematics::new(Vector2::zeros(), 0.0, 0.0))
+            .build()
+        };
+        let mut scene = Scene::new();
+        let a = scene.add_body(circle(0.0));
+        let b = scene.add_body(circle(1.0));
+
+        scene.step(1.0 / 60.0);
+        assert_eq!(scene.contacts.len(), 1);
+
+        assert!(scene.remove_body(a).is_some());
+        assert!(scene.body(a).is_none());
+        assert!(scene.remove_body(a).is_none());
+        assert!(scene.contacts.is_empty());
+        assert_eq!(scene.body_count(), 1);
+
+        // The freed slot is reused, but the old handle must not resolve to the new body
+        let c = scene.add_body(circle(5.0));
+        assert_ne!(a, c);
+        assert!(scene.body(a).is_none());
+        assert_eq!(scene.body(c).unwrap().tx.pos, Point2::new(5.0, 0.0));
+        assert!(scene.body(b).is_some());
+    }
+
+    #[test]
+    fn test_scene_is_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Scene>();
+    }
+
+    fn resting_circle(pos: Point2<f64>) -> Object {
+        Object::builder(Shapes::Circle(Circle {
+            radius: OrderedFloat(1.0),
+        }))
+        .transform(Transform::new(pos))
+        .material(Material::new(
+            1.0,
+            OrderedFloat(1.0),
+            OrderedFloat(0.0),
+            OrderedFloat(0.0),
+        ))
+        .kinematics(Kinematics::new(Vector2::zeros(), 0.0, 0.0))
+        .build()
+    }
+
+    #[test]
+    fn test_zero_gravity_settings() {
+        let mut scene = Scene::with_settings(WorldSettings {
+            gravity: Vector2::zeros(),
+            ..WorldSettings::default()
+        });
+        let a = scene.add_body(resting_circle(Point2::new(0.0, 0.0)));
+
+        for _ in 0..60 {
+            scene.step(1.0 / 60.0);
+        }
+
+        assert_eq!(scene.body(a).unwrap().tx.pos, Point2::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn test_gravity_changed_at_runtime() {
+        let mut scene = Scene::new();
+        let a = scene.add_body(resting_circle(Point2::new(0.0, 0.0)));
+
+        scene.step(1.0 / 60.0);
+        let vel = scene.body(a).unwrap().kinematics.vel;
+        assert_eq!(vel.x, 0.0);
+        assert!(vel.y > 0.0);
+
+        scene.settings.gravity = Vector2::new(-5.0, 0.0);
+        scene.body_mut(a).unwrap().kinematics.vel = Vector2::zeros();
+        scene.step(1.0 / 60.0);
+
+        let vel = scene.body(a).unwrap().kinematics.vel;
+        assert!(vel.x < 0.0);
+        assert_eq!(vel.y, 0.0);
+    }
+
+    #[test]
+    fn test_restitution_threshold_setting() {
+        let bounce = |threshold| {
+            let mut scene = Scene::with_settings(WorldSettings {
+                gravity: Vector2::zeros(),
+                restitution_threshold: Some(threshold),
+                ..WorldSettings::default()
+            });
+            let a = scene.add_body(resting_circle(Point2::new(0.0, 0.0)));
+            let b = scene.add_body(resting_circle(Point2::new(1.9, 0.0)));
+            scene.body_mut(b).unwrap().kinematics.vel = Vector2::new(-1.0, 0.0);
+
+            scene.step(1.0 / 60.0);
+
+            scene.body(b).unwrap().kinematics.vel.x - scene.body(a).unwrap().kinematics.vel.x
+        };
+
+        // Fully elastic below the threshold, perfectly inelastic above it
+        assert!((bounce(0.1) - 1.0).abs() < 1e-9);
+        assert!(bounce(10.0).abs() < 1e-9);
+    }
+
+    fn square(half: f64, pos: Point2<f64>, vel: Vector2<f64>) -> ObjectBuilder {
+        Object::builder(Shapes::Polygon(
+            Polygon::new(vec![
+                Point2::new(-half, -half),
+                Point2::new(half, -half),
+                Point2::new(half, half),
+                Point2::new(-half, half),
+            ])
+            .unwrap(),
+        ))
+        .transform(Transform::new(pos))
+        .material(Material::new(
+            1.0,
+            OrderedFloat(0.0),
+            OrderedFloat(0.0),
+            OrderedFloat(0.0),
+        ))
+        .velocity(vel)
+    }
+
+    #[test]
+    fn test_kinematic_platform_pushes_dynamic_body() {
+        let mut scene = Scene::new();
+        let lift_vel = Vector2::new(0.0, -2.0);
+        let platform = scene.add_body(
+            square(5.0, Point2::new(0.0, 5.0), lift_vel)
+                .body_type(BodyType::Kinematic)
+                .build(),
+        );
+        let block = scene.add_body(square(1.0, Point2::new(0.0, -0.99), Vector2::zeros()).build());
+
+        let dt = 1.0 / 60.0;
+        for _ in 0..60 {
+            scene.step(dt);
+        }
+
+        // The platform ignores gravity and the block's impulses
+        let platform = scene.body(platform).unwrap();
+        assert_eq!(platform.kinematics.vel, lift_vel);
+        assert!((platform.tx.pos.y - 3.0).abs() < 1e-9);
+
+        // The block is carried up with the platform instead of falling through it
+        let block = scene.body(block).unwrap();
+        let gap = platform.tx.pos.y - 5.0 - (block.tx.pos.y + 1.0);
+        assert!(gap.abs() < 0.1, "gap between block and platform: {gap}");
+    }
+
+    #[test]
+    fn test_static_kinematic_pair_skipped() {
+        let mut scene = Scene::new();
+        scene.add_body(
+            square(5.0, Point2::new(0.0, 0.0), Vector2::zeros())
+                .body_type(BodyType::Static)
+                .build(),
+        );
+        let mover = scene.add_body(
+            square(1.0, Point2::new(0.0, 0.0), Vector2::new(3.0, 0.0))
+                .body_type(BodyType::Kinematic)
+                .build(),
+        );
+
+        scene.step(1.0 / 60.0);
+
+        assert!(scene.contacts.is_empty());
+        assert_eq!(scene.body(mover).unwrap().tx.pos, Point2::new(0.05, 0.0));
+    }
+
+    #[test]
+    fn test_static_body_ignores_velocity_and_gravity() {
+        let mut scene = Scene::new();
+        let wall = scene.add_body(
+            square(1.0, Point2::new(0.0, 0.0), Vector2::new(1.0, 1.0))
+                .body_type(BodyType::Static)
+                .build(),
+        );
+
+        scene.step(1.0 / 60.0);
+
+        assert_eq!(scene.body(wall).unwrap().tx.pos, Point2::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn test_identical_scenes_stay_identical() {
+        let build = || {
+            let mut scene = Scene::new();
+            scene.add_body(
+                square(10.0, Point2::new(0.0, 10.0), Vector2::zeros())
+                    .body_type(BodyType::Static)
+                    .build(),
+            );
+            let a = scene.add_body(
+                square(1.0, Point2::new(0.5, -2.0), Vector2::new(1.0, 0.0))
+                    .angular_velocity(0.7)
+                    .build(),
+            );
+            let b = scene.add_body(resting_circle(Point2::new(-0.5, -5.0)));
+            (scene, a, b)
+        };
+
+        let (mut first, a, b) = build();
+        let (mut second, _, _) = build();
+
+        for _ in 0..120 {
+            first.step(1.0 / 60.0);
+            second.step(1.0 / 60.0);
+        }
+
+        for h in [a, b] {
+            let (x, y) = (first.body(h).unwrap(), second.body(h).unwrap());
+            assert_eq!(x.tx.pos, y.tx.pos);
+            assert_eq!(x.tx.orientation(), y.tx.orientation());
+            assert_eq!(x.kinematics.vel, y.kinematics.vel);
+            assert_eq!(x.kinematics.angular_vel, y.kinematics.angular_vel);
+        }
+    }
+
+    #[test]
+    fn test_circle_falls_into_concave_cup() {
+        let mut scene = Scene::new();
+        // A cup open at the top, with its inner floor at y = 3
+        let cup = ConcavePolygon::new(vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(1.0, 0.0),
+            Point2::new(1.0, 3.0),
+            Point2::new(5.0, 3.0),
+            Point2::new(5.0, 0.0),
+            Point2::new(6.0, 0.0),
+            Point2::new(6.0, 4.0),
+            Point2::new(0.0, 4.0),
+        ])
+        .unwrap();
+        // Placed at its centroid so that model space lines up with the outline above
+        scene.add_body(
+            Object::builder(Shapes::ConcavePolygon(cup))
+                .position(Point2::new(3.0, 2.5))
+                .body_type(BodyType::Static)
+                .build(),
+        );
+        let ball = scene.add_body(resting_circle(Point2::new(3.0, -2.0)));
+
+        for _ in 0..300 {
+            scene.step(1.0 / 60.0);
+        }
+
+        // Resting on the convex hull would leave the ball above y = -1
+        let pos = scene.body(ball).unwrap().tx.pos;
+        assert!((pos.y - 2.0).abs() < 0.1, "ball at {pos}");
+        assert!((pos.x - 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_dumbbell_rests_on_both_ends() {
+        let mut scene = Scene::new();
+        scene.add_body(
+            square(10.0, Point2::new(0.0, 10.0), Vector2::zeros())
+                .body_type(BodyType::Static)
+                .build(),
+        );
+        let ball = || {
+            Shapes::Circle(Circle {
+                radius: OrderedFloat(1.0),
+            })
+        };
+        let dumbbell = scene.add_body(
+            Object::builder(ball())
+                .position(Point2::new(-3.0, -3.0))
+                .fixture(Fixture::new(
+                    ball(),
+                    Transform::new(Point2::new(6.0, 0.0)),
+                    Material::default(),
+                ))
+                .build(),
+        );
+
+        // The body stays where it was placed, which becomes its center of mass: the fixtures are
+        // shifted around it, so the ends sit at -3 and +3 from the body's position
+        assert_eq!(
+            scene.body(dumbbell).unwrap().tx.pos,
+            Point2::new(-3.0, -3.0)
+        );
+        assert_eq!(
+            scene.body(dumbbell).unwrap().fixtures[1].local.pos,
+            Point2::new(3.0, 0.0)
+        );
+
+        for _ in 0..300 {
+            scene.step(1.0 / 60.0);
+        }
+
+        let body = scene.body(dumbbell).unwrap();
+        assert!(
+            (body.tx.pos.y + 1.0).abs() < 0.1,
+            "dumbbell at {}",
+            body.tx.pos
+        );
+        for fixture in &body.fixtures {
+            let end = fixture.world_transform(&body.tx).pos;
+            assert!((end.y + 1.0).abs() < 0.1, "end at {end}");
+        }
+    }
+
+    #[test]
+    fn test_fixtures_use_their_own_material() {
+        let mut scene = Scene::new();
+        let slab = |restitution| {
+            (
+                Shapes::Polygon(
+                    Polygon::new(vec![
+                        Point2::new(-4.0, -4.0),
+                        Point2::new(4.0, -4.0),
+                        Point2::new(4.0, 4.0),
+                        Point2::new(-4.0, 4.0),
+                    ])
+                    .unwrap(),
+                ),
+                Material::new(
+                    1.0,
+                    OrderedFloat(restitution),
+                    OrderedFloat(0.0),
+                    OrderedFloat(0.0),
+                ),
+            )
+        };
+        let (bouncy_shape, bouncy) = slab(1.0);
+        let (dead_shape, dead) = slab(0.0);
+        // The slabs end up at x = -5 and x = 5, with their tops at y = 0
+        scene.add_body(
+            Object::builder(bouncy_shape)
+                .material(bouncy)
+                .fixture(Fixture::new(
+                    dead_shape,
+                    Transform::new(Point2::new(10.0, 0.0)),
+                    dead,
+                ))
+                .position(Point2::new(0.0, 4.0))
+                .body_type(BodyType::Static)
+                .build(),
+        );
+        let left = scene.add_body(resting_circle(Point2::new(-5.0, -3.0)));
+        let right = scene.add_body(resting_circle(Point2::new(5.0, -3.0)));
+
+        // Both balls hit their slab after about 0.64 s
+        for _ in 0..48 {
+            scene.step(1.0 / 60.0);
+        }
+
+        assert!(scene.body(left).unwrap().kinematics.vel.y < -3.0);
+        assert!(scene.body(right).unwrap().kinematics.vel.y.abs() < 0.5);
+    }
+
+    /// Builds a scene of randomly placed, sized and moving circles and boxes without gravity.
+    fn random_scene(seed: u64, count: usize) -> Scene {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut scene = Scene::with_settings(WorldSettings {
+            gravity: Vector2::zeros(),
+            ..WorldSettings::default()
+        });
+
+        for _ in 0..count {
+            let pos = Point2::new(rng.gen_range(0.0..50.0), rng.gen_range(0.0..50.0));
+            let size = rng.gen_range(0.2..3.0);
+            let builder = if rng.gen_bool(0.5) {
+                Object::builder(Shapes::Circle(Circle::new(size).unwrap()))
+            } else {
+                square(size, Point2::origin(), Vector2::zeros())
+            };
+
+            scene.add_body(
+                builder
+                    .position(pos)
+                    .orientation(rng.gen_range(0.0..std::f64::consts::TAU))
+                    .random_kinematics(&mut rng)
+                    .build(),
+            );
+        }
+
+        scene
+    }
+
+    /// Returns the pairs of bodies whose tight bounding boxes overlap, by testing every pair.
+    fn brute_force_pairs(scene: &Scene) -> Vec<(BodyHandle, BodyHandle)> {
+        let bodies: Vec<(BodyHandle, Aabb)> = scene
+            .bodies()
+            .map(|(h, body)| (h, body.bounding_box()))
+            .collect();
+
+        let mut pairs = Vec::new();
+        for (i, (ha, a)) in bodies.iter().enumerate() {
+            for (hb, b) in bodies.iter().skip(i + 1) {
+                if a.overlaps(b) {
+                    pairs.push((*ha, *hb));
+                }
+            }
+        }
+
+        pairs
+    }
+
+    /// Creates a broad phase strategy for the tests.
+    type BroadPhaseFn = fn() -> Box<dyn BroadPhase>;
+
+    /// Returns a constructor for every pruning broad phase strategy, named for assertion
+    /// messages.
+    ///
+    /// `BruteForce` is left out as stepping a large scene through every pair is slow.
+    fn broad_phases() -> Vec<(&'static str, BroadPhaseFn)> {
+        vec![
+            ("tree", || Box::new(TreeBroadPhase::default())),
+            ("sweep x", || Box::new(SweepAndPrune::new(Axis::X))),
+            ("sweep y", || Box::new(SweepAndPrune::new(Axis::Y))),
+            ("grid", || Box::new(SpatialHashGrid::new(2.0))),
+        ]
+    }
+
+    /// Returns the candidate pairs of the scene whose tight bounding boxes actually overlap.
+    fn overlapping_candidates(scene: &mut Scene) -> Vec<(BodyHandle, BodyHandle)> {
+        // Candidates may come from fattened boxes or no boxes at all, so drop the ones whose
+        // tight boxes miss
+        let mut candidates = scene.candidate_pairs();
+        candidates.retain(|&(a, b)| {
+            let a = scene.body(a).unwrap().bounding_box();
+            a.overlaps(&scene.body(b).unwrap().bounding_box())
+        });
+
+        candidates
+    }
+
+    #[test]
+    fn test_candidate_pairs_match_brute_force() {
+        for (name, broad_phase) in broad_phases() {
+            for seed in 0..3 {
+                let mut scene = random_scene(seed, 150);
+                scene.set_broad_phase(broad_phase());
+
+                for step in 0..20 {
+                    assert_eq!(
+                        overlapping_candidates(&mut scene),
+                        brute_force_pairs(&scene),
+                        "{name}, seed {seed}, step {step}"
+                    );
+
+                    scene.step(1.0 / 60.0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_candidate_pairs_after_removal_and_teleport() {
+        let mut broad_phases = broad_phases();
+        broad_phases.push(("brute force", || Box::new(BruteForce::new())));
+
+        for (name, broad_phase) in broad_phases {
+            let mut scene = random_scene(42, 100);
+            scene.set_broad_phase(broad_phase());
+            let handles: Vec<BodyHandle> = scene.bodies().map(|(h, _)| h).collect();
+
+            for &h in handles.iter().step_by(3) {
+                scene.remove_body(h);
+            }
+            for &h in handles.iter().skip(1).step_by(3) {
+                scene.body_mut(h).unwrap().tx.pos = Point2::new(25.0, 25.0);
+            }
+
+            assert_eq!(
+                overlapping_candidates(&mut scene),
+                brute_force_pairs(&scene),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_brute_force_returns_every_pair() {
+        let mut scene = random_scene(7, 10);
+        scene.set_broad_phase(Box::new(BruteForce::new()));
+
+        assert_eq!(scene.candidate_pairs().len(), 10 * 9 / 2);
+    }
+
+    /// Builds a static scene with a ground box, a circle above it and a hammer lying on its side.
+    fn ray_scene() -> (Scene, BodyHandle, BodyHandle, BodyHandle) {
+        let mut scene = Scene::new();
+        let ground = scene.add_body(
+            square(1.0, Point2::new(0.0, -1.0), Vector2::zeros())
+                .body_type(BodyType::Static)
+                .build(),
+        );
+        let ball = scene.add_body(
+            Object::builder(Shapes::Circle(Circle::new(0.5).unwrap()))
+                .position(Point2::new(0.0, 2.0))
+                .body_type(BodyType::Static)
+                .build(),
+        );
+
+        // A thin handle along the x axis with a head offset to its right end
+        let handle_shape = Polygon::new(vec![
+            Point2::new(-1.0, -0.1),
+            Point2::new(1.0, -0.1),
+            Point2::new(1.0, 0.1),
+            Point2::new(-1.0, 0.1),
+        ])
+        .unwrap();
+        let head = Fixture::new(
+            Shapes::Circle(Circle::new(0.4).unwrap()),
+            Transform::new(Point2::new(1.2, 0.0)),
+            Material::default(),
+        );
+        let hammer = scene.add_body(
+            Object::builder(Shapes::Polygon(handle_shape))
+                .fixture(head)
+                .position(Point2::new(5.0, 0.0))
+                .orientation(std::f64::consts::FRAC_PI_2)
+                .body_type(BodyType::Static)
+                .build(),
+        );
+
+        (scene, ground, ball, hammer)
+    }
+
+    #[test]
+    fn test_ray_cast_closest_and_all() {
+        let (scene, ground, ball, _) = ray_scene();
+        let down = Vector2::new(0.0, -1.0);
+
+        let hit = scene
+            .ray_cast(Point2::new(0.0, 5.0), down, 10.0, |_, _| true)
+            .unwrap();
+        assert_eq!(hit.handle, ball);
+        assert!((hit.point - Point2::new(0.0, 2.5)).norm() < 1e-9);
+        assert!((hit.normal - Vector2::new(0.0, 1.0)).norm() < 1e-9);
+        assert!((hit.fraction - 0.25).abs() < 1e-9);
+
+        let hit = scene
+            .ray_cast(Point2::new(0.0, 5.0), down, 10.0, |h, _| h != ball)
+            .unwrap();
+        assert_eq!(hit.handle, ground);
+        assert!((hit.point - Point2::new(0.0, 0.0)).norm() < 1e-9);
+
+        let hits = scene.ray_cast_all(Point2::new(0.0, 5.0), down, 10.0, |_, _| true);
+        let handles: Vec<BodyHandle> = hits.iter().map(|hit| hit.handle).collect();
+        assert_eq!(handles, vec![ball, ground]);
+
+        // Stopping short of everything, and a zero direction
+        assert!(scene
+            .ray_cast(Point2::new(0.0, 5.0), down, 2.0, |_, _| true)
+            .is_none());
+        assert!(scene
+            .ray_cast(Point2::new(0.0, 5.0), Vector2::zeros(), 10.0, |_, _| true)
+            .is_none());
+    }
+
+    #[test]
+    fn test_ray_cast_respects_fixtures_and_orientation() {
+        let (scene, _, _, hammer) = ray_scene();
+        let body = scene.body(hammer).unwrap();
+        let head_center = body
+            .part(PartId {
+                fixture: 1,
+                part: 0,
+            })
+            .1
+            .pos;
+
+        // The hammer stands upright, so a horizontal ray through the head hits the circle
+        let hit = scene
+            .ray_cast(
+                Point2::new(10.0, head_center.y),
+                -Vector2::x(),
+                10.0,
+                |_, _| true,
+            )
+            .unwrap();
+        assert_eq!(hit.handle, hammer);
+        assert_eq!(
+            hit.part,
+            PartId {
+                fixture: 1,
+                part: 0
+            }
+        );
+        assert!((hit.point.x - (head_center.x + 0.4)).abs() < 1e-9);
+
+        // A vertical ray from below enters through the bottom of the handle
+        let bottom = body.tx.pos.x;
+        let hit = scene
+            .ray_cast(Point2::new(bottom, -10.0), Vector2::y(), 20.0, |h, _| {
+                h == hammer
+            })
+            .unwrap();
+        assert_eq!(
+            hit.part,
+            PartId {
+                fixture: 0,
+                part: 0
+            }
+        );
+        assert!((hit.normal - Vector2::new(0.0, -1.0)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_ray_cast_from_inside_compound_body() {
+        let (scene, _, _, hammer) = ray_scene();
+        let body = scene.body(hammer).unwrap();
+        let part_center = |fixture| body.part(PartId { fixture, part: 0 }).1.pos;
+        let handle_center = part_center(0);
+        let head_center = part_center(1);
+
+        // Starting inside the handle, the ray leaves the hammer without hitting its head
+        let direction = head_center - handle_center;
+        assert!(scene
+            .ray_cast(handle_center, direction, 10.0, |h, _| h == hammer)
+            .is_none());
+        assert!(scene
+            .ray_cast_all(handle_center, direction, 10.0, |_, _| true)
+            .iter()
+            .all(|hit| hit.handle != hammer));
+
+        // Coming from outside, it still enters through the handle
+        let outside = handle_center - direction;
+        let hit = scene
+            .ray_cast(outside, direction, 10.0, |h, _| h == hammer)
+            .unwrap();
+        assert_eq!(hit.part, PartId::default());
+    }
+
+    #[test]
+    fn test_ray_cast_sees_moved_bodies() {
+        for (name, broad_phase) in broad_phases() {
+            let (mut scene, _, ball, _) = ray_scene();
+            scene.set_broad_phase(broad_phase());
+            let origin = Point2::new(-10.0, 20.0);
+
+            // Moved by hand, then by a step
+            scene.body_mut(ball).unwrap().tx.pos = Point2::new(0.0, 20.0);
+            let hit = scene.ray_cast(origin, Vector2::x(), 20.0, |_, _| true);
+            assert_eq!(hit.map(|hit| hit.handle), Some(ball), "{name}");
+
+            let body = scene.body_mut(ball).unwrap();
+            body.body_type = BodyType::Kinematic;
+            body.kinematics.vel = Vector2::new(0.0, 60.0);
+            scene.step(1.0 / 60.0);
+            assert!(
+                scene
+                    .ray_cast(origin, Vector2::x(), 20.0, |_, _| true)
+                    .is_none(),
+                "{name}"
+            );
+            let hit = scene.ray_cast(origin + Vector2::y(), Vector2::x(), 20.0, |_, _| true);
+            assert_eq!(hit.map(|hit| hit.handle), Some(ball), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_query_point() {
+        let (scene, ground, ball, hammer) = ray_scene();
+        let body = scene.body(hammer).unwrap();
+        let head_center = body
+            .part(PartId {
+                fixture: 1,
+                part: 0,
+            })
+            .1
+            .pos;
+
+        assert_eq!(scene.query_point(Point2::new(0.2, 2.2)), vec![ball]);
+        assert_eq!(scene.query_point(Point2::new(0.9, -1.9)), vec![ground]);
+        assert_eq!(scene.query_point(head_center), vec![hammer]);
+
+        // Inside the hammer's bounding box, but beside its upright handle
+        let handle_center = body.part(PartId::default()).1.pos;
+        let beside = handle_center + Vector2::new(0.3, -0.5);
+        assert!(body.bounding_box().contains_point(&beside));
+        assert!(scene.query_point(beside).is_empty());
+
+        // Inside the ball's bounding box but outside the circle
+        assert!(scene.query_point(Point2::new(0.45, 2.45)).is_empty());
+    }
+
+    #[test]
+    fn test_query_aabb() {
+        let (mut scene, ground, ball, hammer) = ray_scene();
+
+        assert_eq!(
+            scene.query_aabb(Point2::new(-0.2, -0.2), Point2::new(0.2, 2.0)),
+            vec![ground, ball]
+        );
+        assert!(scene
+            .query_aabb(Point2::new(2.0, 3.0), Point2::new(3.0, 4.0))
+            .is_empty());
+
+        // The hammer stands upright, so its bounds are narrow but tall
+        let pos = scene.body(hammer).unwrap().tx.pos;
+        assert_eq!(
+            scene.query_aabb(pos + Vector2::new(-0.2, 0.8), pos + Vector2::new(0.2, 1.0)),
+            vec![hammer]
+        );
+        assert!(scene
+            .query_aabb(pos + Vector2::new(0.6, -0.1), pos + Vector2::new(1.0, 0.1))
+            .is_empty());
+
+        // Moved bodies are found at their new position straight away
+        scene.body_mut(ball).unwrap().tx.pos = Point2::new(2.5, 3.5);
+        assert_eq!(
+            scene.query_aabb(Point2::new(2.0, 3.0), Point2::new(3.0, 4.0)),
+            vec![ball]
+        );
+    }
+
+    #[test]
+    fn test_shape_cast_circle_and_polygon() {
+        let (scene, ground, ball, hammer) = ray_scene();
+        let circle = Shapes::Circle(Circle::new(0.25).unwrap());
+
+        // Dropped next to the ball, landing on the ground's top face at y = 0
+        let start = Transform::new(Point2::new(0.8, 4.0));
+        let hit = scene
+            .shape_cast(&circle, start, Vector2::new(0.0, -8.0), |_, _| true)
+            .unwrap();
+        assert_eq!(hit.handle, ground);
+        assert!((hit.toi - 3.75 / 8.0).abs() < 1e-3);
+        assert!((hit.point - Point2::new(0.8, 0.0)).norm() < 1e-2);
+        assert!((hit.normal - Vector2::new(0.0, 1.0)).norm() < 1e-6);
+
+        // Straight down onto the ball, unless it is filtered out
+        let start = Transform::new(Point2::new(0.0, 4.0));
+        let hit = scene
+            .shape_cast(&circle, start, Vector2::new(0.0, -8.0), |_, _| true)
+            .unwrap();
+        assert_eq!(hit.handle, ball);
+        let hit = scene
+            .shape_cast(&circle, start, Vector2::new(0.0, -8.0), |h, _| h != ball)
+            .unwrap();
+        assert_eq!(hit.handle, ground);
+
+        // A tilted box swept sideways into the head of the upright hammer
+        let head = scene
+            .body(hammer)
+            .unwrap()
+            .part(PartId {
+                fixture: 1,
+                part: 0,
+            })
+            .1
+            .pos;
+        let square = square(0.2, Point2::origin(), Vector2::zeros()).build();
+        let start = Transform::with_orientation(Point2::new(head.x - 3.0, head.y), 0.3);
+        let hit = scene
+            .shape_cast(
+                &square.fixtures[0].shape,
+                start,
+                Vector2::new(6.0, 0.0),
+                |_, _| true,
+            )
+            .unwrap();
+        assert_eq!(hit.handle, hammer);
+        assert_eq!(
+            hit.part,
+            PartId {
+                fixture: 1,
+                part: 0
+            }
+        );
+        assert!(hit.normal.x < -0.9);
+        assert!((hit.point - head).norm() - 0.4 < 1e-2);
+
+        // Too short to reach anything
+        assert!(scene
+            .shape_cast(&circle, start, Vector2::new(1.0, 0.0), |_, _| true)
+            .is_none());
+    }
+
+    #[test]
+    fn test_shape_cast_concave_shape() {
+        let (scene, ground, _, _) = ray_scene();
+        // A U shape whose arms straddle the ball on the ground
+        let cup = Shapes::ConcavePolygon(
+            ConcavePolygon::new(vec![
+                Point2::new(-2.0, 0.0),
+                Point2::new(2.0, 0.0),
+                Point2::new(2.0, 5.0),
+                Point2::new(1.0, 5.0),
+                Point2::new(1.0, 1.0),
+                Point2::new(-1.0, 1.0),
+                Point2::new(-1.0, 5.0),
+                Point2::new(-2.0, 5.0),
+            ])
+            .unwrap(),
+        );
+
+        // Upside down and lowered over the ball, the arms land on the ground at y = 0
+        let start = Transform::with_orientation(Point2::new(0.0, 10.0), std::f64::consts::PI);
+        let hit = scene
+            .shape_cast(&cup, start, Vector2::new(0.0, -10.0), |_, _| true)
+            .unwrap();
+        assert_eq!(hit.handle, ground);
+        assert!((hit.toi - 0.5).abs() < 1e-2);
+        assert!((hit.normal - Vector2::new(0.0, 1.0)).norm() < 1e-6);
+
+        // Starting out already touching the ground
+        let start = Transform::with_orientation(Point2::new(0.0, 4.9), std::f64::consts::PI);
+        assert!(scene
+            .shape_cast(&cup, start, Vector2::new(0.0, -1.0), |h, _| h == ground)
+            .is_none());
+    }
+
+    /// An ellipse defined outside the built-in shapes, to exercise `Shapes::Custom`.
+    struct Ellipse {
+        /// The half width along the model x axis.
+        a: f64,
+        /// The half height along the model y axis.
+        b: f64,
+    }
+
+    impl SupportMap for Ellipse {
+        fn local_support(&self, dir: &Vector2<f64>) -> Point2<f64> {
+            let scaled = Vector2::new(self.a * self.a * dir.x, self.b * self.b * dir.y);
+            let len = (scaled.x * dir.x + scaled.y * dir.y).sqrt();
+            if len == 0.0 {
+                return Point2::new(self.a, 0.0);
+            }
+
+            Point2::from(scaled / len)
+        }
+    }
+
+    impl Shape for Ellipse {
+        fn calculate_mass_data(&mut self, density: f64) -> MassData {
+            let m = std::f64::consts::PI * self.a * self.b * density;
+
+            MassData::new(m, 0.25 * m * (self.a * self.a + self.b * self.b))
+        }
+
+        fn centroid(&self) -> Point2<f64> {
+            Point2::origin()
+        }
+
+        fn bounding_box(&self, tx: &Transform) -> Aabb {
+            let (x, y) = (Vector2::x(), Vector2::y());
+
+            Aabb::new(
+                Point2::new(self.support(tx, &-x).x, self.support(tx, &-y).y),
+                Point2::new(self.support(tx, &x).x, self.support(tx, &y).y),
+            )
+        }
+
+        fn ray_cast(&self, ray: &Ray, tx: &Transform) -> Option<(f64, Vector2<f64>)> {
+            // Squash the ellipse into a unit circle
+            let o = tx.to_local(&ray.origin);
+            let d = tx.rotate_to_local(&ray.dir);
+            let o = Vector2::new(o.x / self.a, o.y / self.b);
+            let d = Vector2::new(d.x / self.a, d.y / self.b);
+
+            let (qa, qb, qc) = (d.norm_squared(), o.dot(&d), o.norm_squared() - 1.0);
+            let disc = qb * qb - qa * qc;
+            if qc <= 0.0 || qb > 0.0 || disc < 0.0 {
+                return None;
+            }
+
+            let distance = (-qb - disc.sqrt()) / qa;
+            if distance > ray.max_distance {
+                return None;
+            }
+
+            let p = tx.to_local(&ray.point_at(distance));
+            let normal = Vector2::new(p.x / (self.a * self.a), p.y / (self.b * self.b));
+
+            Some((distance, tx.rotate_to_world(&normal.normalize())))
+        }
+
+        fn discriminant(&self) -> ShapeDiscriminant {
+            ShapeDiscriminant::Custom
+        }
+    }
+
+    fn ellipse(a: f64, b: f64) -> Shapes {
+        Shapes::Custom(Box::new(Ellipse { a, b }))
+    }
+
+    #[test]
+    fn test_custom_shape_collides_with_built_in_shapes() {
+        let mut scene = Scene::new();
+        scene.add_body(
+            square(5.0, Point2::new(0.0, 6.0), Vector2::zeros())
+                .body_type(BodyType::Static)
+                .build(),
+        );
+        let lying = scene.add_body(
+            Object::builder(ellipse(2.0, 0.5))
+                .position(Point2::new(-2.5, -1.0))
+                .build(),
+        );
+        let stand = scene.add_body(
+            Object::builder(ellipse(1.0, 2.0))
+                .position(Point2::new(3.0, -1.0))
+                .body_type(BodyType::Static)
+                .build(),
+        );
+        let ball = scene.add_body(resting_circle(Point2::new(3.0, -6.0)));
+
+        for _ in 0..120 {
+            scene.step(1.0 / 60.0);
+        }
+
+        // Resting on the floor's top face at y = 1
+        let body = scene.body(lying).unwrap();
+        assert!(
+            (body.tx.pos.y - 0.5).abs() < 0.1,
+            "ellipse at {}",
+            body.tx.pos
+        );
+        assert!(body.tx.orientation().abs() < 1e-3);
+
+        // Caught on the top of the upright ellipse
+        let pos = scene.body(ball).unwrap().tx.pos;
+        assert!((pos - Point2::new(3.0, -4.0)).norm() < 0.1, "ball at {pos}");
+        assert_eq!(scene.body(stand).unwrap().tx.pos, Point2::new(3.0, -1.0));
+    }
+
+    #[test]
+    fn test_custom_shape_queries() {
+        let mut scene = Scene::new();
+        let egg = scene.add_body(
+            Object::builder(ellipse(2.0, 1.0))
+                .position(Point2::new(1.0, 1.0))
+                .orientation(std::f64::consts::FRAC_PI_2)
+                .body_type(BodyType::Static)
+                .build(),
+        );
+
+        // Stood upright, the ellipse reaches 2 above and below its center but only 1 to the sides
+        let hit = scene
+            .ray_cast(Point2::new(1.0, 5.0), -Vector2::y(), 10.0, |_, _| true)
+            .unwrap();
+        assert_eq!(hit.handle, egg);
+        assert!((hit.point - Point2::new(1.0, 3.0)).norm() < 1e-9);
+        assert!((hit.normal - Vector2::y()).norm() < 1e-9);
+        assert!(scene
+            .ray_cast(Point2::new(2.5, 5.0), -Vector2::y(), 10.0, |_, _| true)
+            .is_none());
+
+        assert_eq!(scene.query_point(Point2::new(1.5, 2.5)), vec![egg]);
+        assert!(scene.query_point(Point2::new(1.9, 2.5)).is_empty());
+        assert_eq!(
+            scene.query_aabb(Point2::new(2.1, 2.6), Point2::new(2.5, 3.0)),
+            Vec::new()
+        );
+        assert_eq!(
+            scene.query_aabb(Point2::new(1.8, -1.0), Point2::new(2.2, 3.0)),
+            vec![egg]
+        );
+
+        // Swept sideways into the ellipse, a small circle stops against its right side
+        let circle = Shapes::Circle(Circle::new(0.5).unwrap());
+        let hit = scene
+            .shape_cast(
+                &circle,
+                Transform::new(Point2::new(6.0, 1.0)),
+                Vector2::new(-10.0, 0.0),
+                |_, _| true,
+            )
+            .unwrap();
+        assert_eq!(hit.handle, egg);
+        assert!((hit.toi - 0.35).abs() < 1e-2);
+        assert!((hit.normal - Vector2::x()).norm() < 1e-6);
+    }
+
+    fn filtered_circle(pos: Point2<f64>, filter: CollisionFilter) -> Object {
+        let mut body = resting_circle(pos);
+        body.fixtures[0].filter = filter;
+        body
+    }
+
+    #[test]
+    fn test_collision_filters() {
+        const DEBRIS: u16 = 0x0002;
+
+        let mut scene = Scene::new();
+        scene.add_body(
+            square(10.0, Point2::new(0.0, 11.0), Vector2::zeros())
+                .body_type(BodyType::Static)
+                .build(),
+        );
+
+        // Debris ignores other debris, so the upper piece falls straight through the lower one
+        let debris = CollisionFilter::new(DEBRIS, !DEBRIS, 0);
+        let low = scene.add_body(filtered_circle(Point2::new(-4.0, -2.0), debris));
+        let high = scene.add_body(filtered_circle(Point2::new(-4.0, -5.0), debris));
+
+        // A negative group keeps a shot from hitting the body that fired it
+        let team = CollisionFilter::new(0x0001, 0xFFFF, -1);
+        let shooter = scene.add_body(filtered_circle(Point2::new(4.0, -2.0), team));
+        let shot = scene.add_body(filtered_circle(Point2::new(4.0, -5.0), team));
+
+        // Ordinary bodies still stack
+        let bottom = scene.add_body(resting_circle(Point2::new(0.0, -2.0)));
+        let top = scene.add_body(resting_circle(Point2::new(0.0, -5.0)));
+
+        for _ in 0..180 {
+            scene.step(1.0 / 60.0);
+        }
+
+        let y = |h: BodyHandle| scene.body(h).unwrap().tx.pos.y;
+        for h in [low, high, shooter, shot, bottom] {
+            assert!(y(h).abs() < 0.1, "resting at {}", y(h));
+        }
+        assert!(
+            (y(bottom) - y(top) - 2.0).abs() < 0.1,
+            "stacked at {}",
+            y(top)
+        );
+    }
+
+    #[test]
+    fn test_contact_filter_callback() {
+        let mut scene = Scene::new();
+        let floor = scene.add_body(
+            square(10.0, Point2::new(0.0, 11.0), Vector2::zeros())
+                .body_type(BodyType::Static)
+                .build(),
+        );
+        let ghost = scene.add_body(resting_circle(Point2::new(-4.0, -2.0)));
+        let ball = scene.add_body(resting_circle(Point2::new(4.0, -2.0)));
+
+        // Handles are stable, so the callback can refer to bodies added earlier
+        scene.set_contact_filter(move |a, _, b, _| a != ghost && b != ghost);
+        for _ in 0..60 {
+            scene.step(1.0 / 60.0);
+        }
+
+        assert!(scene.body(ghost).unwrap().tx.pos.y > 1.0);
+        assert!(scene.body(ball).unwrap().tx.pos.y.abs() < 0.1);
+        assert!(scene.contacts.iter().all(|m| m.a != ghost && m.b != ghost));
+
+        // Without the callback, the ghost is caught by the floor again
+        scene.clear_contact_filter();
+        scene.step(1.0 / 60.0);
+        assert!(scene
+            .contacts
+            .iter()
+            .any(|m| (m.a, m.b) == (floor, ghost) || (m.a, m.b) == (ghost, floor)));
+    }
+
+    #[test]
+    fn test_sensor_reports_overlaps_without_response() {
+        let mut scene = Scene::new();
+        let zone = scene.add_body(
+            square(1.0, Point2::new(0.0, -4.0), Vector2::zeros())
+                .body_type(BodyType::Static)
+                .sensor(true)
+                .build(),
+        );
+        let ball = scene.add_body(resting_circle(Point2::new(0.0, -10.0)));
+        let overlap = SensorOverlap {
+            sensor: zone,
+            sensor_part: PartId::default(),
+            visitor: ball,
+            visitor_part: PartId::default(),
+        };
+
+        let mut events = Vec::new();
+        for i in 0..120 {
+            // Overlaps are found before the step moves the ball
+            let y = scene.body(ball).unwrap().tx.pos.y;
+            scene.step(1.0 / 60.0);
+            events.extend(scene.drain_sensor_events().map(|event| (i, event)));
+
+            let inside = scene.sensor_overlaps().any(|o| *o == overlap);
+            assert_eq!(inside, (y + 4.0).abs() < 2.0, "at {y}");
+
+            // The ball falls freely through the zone
+            let vel = scene.body(ball).unwrap().kinematics.vel;
+            assert!((vel.y - 9.8 * (i + 1) as f64 / 60.0).abs() < 1e-9);
+        }
+
+        let [(entered, begin), (left, end)] = events.as_slice() else {
+            panic!("expected a begin and an end event, got {events:?}");
+        };
+        assert!(entered < left);
+        assert_eq!(*begin, SensorEvent::Begin(overlap));
+        assert_eq!(*end, SensorEvent::End(overlap));
+        assert_eq!(scene.drain_sensor_events().count(), 0);
+    }
+
+    #[test]
+    fn test_static_sensor_detects_kinematic_body() {
+        let mut scene = Scene::new();
+        let zone = scene.add_body(
+            square(1.0, Point2::new(0.0, 0.0), Vector2::zeros())
+                .body_type(BodyType::Static)
+                .sensor(true)
+                .build(),
+        );
+        let platform = scene.add_body(
+            square(0.5, Point2::new(-5.0, 0.0), Vector2::new(6.0, 0.0))
+                .body_type(BodyType::Kinematic)
+                .build(),
+        );
+        let overlap = SensorOverlap {
+            sensor: zone,
+            sensor_part: PartId::default(),
+            visitor: platform,
+            visitor_part: PartId::default(),
+        };
+
+        let mut events = Vec::new();
+        for _ in 0..120 {
+            scene.step(1.0 / 60.0);
+            events.extend(scene.drain_sensor_events());
+        }
+
+        // The platform passes straight through, untouched by the zone
+        assert_eq!(
+            events,
+            vec![SensorEvent::Begin(overlap), SensorEvent::End(overlap)]
+        );
+        let platform = scene.body(platform).unwrap();
+        assert_eq!(platform.kinematics.vel, Vector2::new(6.0, 0.0));
+        assert!(scene.contacts.iter().all(|m| m.is_sensor));
+    }
+
+    #[test]
+    fn test_contact_events() {
+        let mut scene = Scene::new();
+        let floor = scene.add_body(
+            square(10.0, Point2::new(0.0, 11.0), Vector2::zeros())
+                .body_type(BodyType::Static)
+                .build(),
+        );
+        let block = scene.add_body(square(1.0, Point2::new(0.0, -2.0), Vector2::zeros()).build());
+
+        let mut events = Vec::new();
+        for _ in 0..120 {
+            scene.step(1.0 / 60.0);
+            events.push(scene.drain_contact_events().collect::<Vec<_>>());
+        }
+
+        // Nothing until the block lands, then a begin followed by a persist every step
+        let landed = events.iter().position(|e| !e.is_empty()).unwrap();
+        assert!(events[..landed].iter().all(|e| e.is_empty()));
+        assert!(matches!(
+            events[landed].as_slice(),
+            [ContactEvent::Begin(info)] if (info.a, info.b) == (floor, block)
+        ));
+        for step in &events[landed + 1..] {
+            assert!(matches!(step.as_slice(), [ContactEvent::Persist(_)]));
+        }
+
+        // At rest, the floor's impulses cancel the weight gained over a step
+        let Some(ContactEvent::Persist(info)) = events.last().unwrap().first() else {
+            unreachable!();
+        };
+        let weight = scene.body(block).unwrap().mass_data.mass * 9.8 / 60.0;
+        let total: f64 = info.normal_impulses[..info.contact_count].iter().sum();
+        assert_eq!(info.contact_count, 2);
+        assert!(
+            (total - weight).abs() < 0.05 * weight,
+            "{total} vs {weight}"
+        );
+        assert!((info.normal - Vector2::new(0.0, -1.0)).norm() < 1e-9);
+        assert!(info.tangent_impulses.iter().all(|t| t.abs() < 1e-9));
+
+        // Lifting the block away ends the contact, reporting where it last was
+        scene.body_mut(block).unwrap().tx.pos.y = -5.0;
+        scene.step(1.0 / 60.0);
+        let ended: Vec<ContactEvent> = scene.drain_contact_events().collect();
+        assert!(matches!(
+            ended.as_slice(),
+            [ContactEvent::End(last)] if last.contacts == info.contacts
+        ));
+        assert_eq!(scene.drain_contact_events().count(), 0);
+    }
+
+    fn frictional_material() -> Material {
+        Material::new(1.0, OrderedFloat(0.0), OrderedFloat(0.5), OrderedFloat(0.5))
+    }
+
+    #[test]
+    fn test_pre_solve_one_way_platform() {
+        let mut scene = Scene::new();
+        let platform = Polygon::new(vec![
+            Point2::new(-3.0, -0.25),
+            Point2::new(3.0, -0.25),
+            Point2::new(3.0, 0.25),
+            Point2::new(-3.0, 0.25),
+        ])
+        .unwrap();
+        scene.add_body(
+            Object::builder(Shapes::Polygon(platform))
+                .body_type(BodyType::Static)
+                .build(),
+        );
+        let ball = scene.add_body(
+            Object::builder(Shapes::Circle(Circle::new(1.0).unwrap()))
+                .position(Point2::new(0.0, 3.0))
+                .velocity(Vector2::new(0.0, -12.0))
+                .build(),
+        );
+
+        // The platform is added first, so the normal points from it to the ball. Only a ball
+        // coming down onto its top lands on it.
+        scene.set_pre_solve_hook(|m, _, ball| {
+            m.set_enabled(m.normal().y < -0.5 && ball.kinematics.vel.y >= 0.0);
+        });
+
+        let mut highest = f64::INFINITY;
+        for _ in 0..180 {
+            scene.step(1.0 / 60.0);
+            highest = highest.min(scene.body(ball).unwrap().tx.pos.y);
+        }
+
+        assert!(highest < -4.0, "only rose to {highest}");
+        let y = scene.body(ball).unwrap().tx.pos.y;
+        assert!((y + 1.25).abs() < 0.1, "resting at {y}");
+    }
+
+    #[test]
+    fn test_pre_solve_conveyor_and_restitution() {
+        let mut scene = Scene::new();
+        scene.add_body(
+            square(10.0, Point2::new(0.0, 11.0), Vector2::zeros())
+                .material(frictional_material())
+                .body_type(BodyType::Static)
+                .build(),
+        );
+        let parcel = scene.add_body(
+            square(1.0, Point2::new(-4.0, -0.95), Vector2::zeros())
+                .material(frictional_material())
+                .build(),
+        );
+        let ball = scene.add_body(resting_circle(Point2::new(4.0, -5.0)));
+
+        // The floor's normal points up, so the belt runs towards negative x under the parcel,
+        // while the ball is made perfectly bouncy
+        scene.set_pre_solve_hook(move |m, _, _| {
+            if m.b() == parcel {
+                m.set_tangent_speed(2.0);
+            } else {
+                m.set_restitution(OrderedFloat(1.0));
+            }
+        });
+
+        let mut bounced = f64::INFINITY;
+        for i in 0..120 {
+            scene.step(1.0 / 60.0);
+            if i > 60 {
+                bounced = bounced.min(scene.body(ball).unwrap().tx.pos.y);
+            }
+        }
+
+        let vel = scene.body(parcel).unwrap().kinematics.vel;
+        assert!((vel.x + 2.0).abs() < 0.05, "moving at {vel}");
+        assert!(bounced < -4.0, "only bounced to {bounced}");
+    }
+
+    /// Rolls a ball at a wall slower than the scene's resting speed, with the given pre-solve
+    /// restitution override, and returns its velocity afterwards.
+    fn slow_bounce(restitution: Option<f64>) -> f64 {
+        let mut scene = Scene::with_settings(WorldSettings {
+            gravity: Vector2::zeros(),
+            restitution_threshold: Some(1.0),
+            ..WorldSettings::default()
+        });
+        scene.add_body(
+            square(1.0, Point2::new(3.0, 0.0), Vector2::zeros())
+                .body_type(BodyType::Static)
+                .build(),
+        );
+        let mut ball = resting_circle(Point2::new(0.5, 0.0));
+        ball.kinematics.vel = Vector2::new(0.5, 0.0);
+        let ball = scene.add_body(ball);
+
+        if let Some(restitution) = restitution {
+            scene.set_pre_solve_hook(move |m, _, _| m.set_restitution(OrderedFloat(restitution)));
+        }
+
+        for _ in 0..120 {
+            scene.step(1.0 / 60.0);
+        }
+
+        scene.body(ball).unwrap().kinematics.vel.x
+    }
+
+    #[test]
+    fn test_pre_solve_restitution_applies_to_slow_contacts() {
+        let stopped = slow_bounce(None);
+        assert!(stopped.abs() < 1e-9, "moving at {stopped}");
+
+        let bounced = slow_bounce(Some(1.0));
+        assert!((bounced + 0.5).abs() < 1e-9, "moving at {bounced}");
+    }
+
+    #[test]
+    fn test_joined_bodies() {
+        let mut scene = Scene::with_settings(WorldSettings {
+            gravity: Vector2::zeros(),
+            ..WorldSettings::default()
+        });
+        let a = scene.add_body(resting_circle(Point2::new(0.0, 0.0)));
+        let b = scene.add_body(resting_circle(Point2::new(1.5, 0.0)));
+        let c = scene.add_body(resting_circle(Point2::new(10.0, 0.0)));
+        let joint = scene
+            .add_joint(DistanceJoint::new(
+                a,
+                b,
+                Point2::origin(),
+                Point2::origin(),
+                1.5,
+            ))
+            .unwrap();
+        scene
+            .add_joint(DistanceJoint::new(
+                b,
+                c,
+                Point2::origin(),
+                Point2::origin(),
+                8.5,
+            ))
+            .unwrap();
+
+        // Joints need two different bodies that are still in the scene
+        let tie = |x, y| DistanceJoint::new(x, y, Point2::origin(), Point2::origin(), 1.0);
+        assert!(scene.add_joint(tie(a, a)).is_none());
+
+        // Joined bodies pass through each other unless the joint says otherwise
+        scene.step(1.0 / 60.0);
+        assert!(scene.contacts.is_empty());
+
+        if let Some(Joint::Distance(joint)) = scene.joint_mut(joint) {
+            joint.collide_connected = true;
+        }
+        scene.step(1.0 / 60.0);
+        assert_eq!(scene.contacts.len(), 1);
+
+        scene.remove_body(b);
+        assert_eq!(scene.joints().count(), 0);
+        assert!(scene.joint(joint).is_none());
+        assert!(scene.add_joint(tie(a, b)).is_none());
+    }
+
+    #[test]
+    fn test_retargeted_joint_is_dropped() {
+        let mut scene = Scene::new();
+        let a = scene.add_body(resting_circle(Point2::new(0.0, 0.0)));
+        let b = scene.add_body(resting_circle(Point2::new(5.0, 0.0)));
+        let removed = scene.add_body(resting_circle(Point2::new(10.0, 0.0)));
+        scene.remove_body(removed);
+
+        let tie = |x, y| DistanceJoint::new(x, y, Point2::origin(), Point2::origin(), 5.0);
+        let joint = scene.add_joint(tie(a, b)).unwrap();
 
-        assert_ne!(obj.tx.pos, initial_pos);
-        assert_ne!(obj.tx.orientation, initial_orientation);
+        // Swapping in a joint to a removed body gets past the checks in `add_joint`
+        *scene.joint_mut(joint).unwrap() = tie(a, removed).into();
+        scene.step(1.0 / 60.0);
+        assert!(scene.joint(joint).is_none());
     }
 }
@@ -0,0 +1,25 @@
+use crate::fixture::PartId;
+use crate::scene::BodyHandle;
+
+/// A sensor part and another body's part that overlap it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SensorOverlap {
+    /// The body with the sensor fixture.
+    pub sensor: BodyHandle,
+    /// The sensor's convex part.
+    pub sensor_part: PartId,
+    /// The body overlapping the sensor.
+    pub visitor: BodyHandle,
+    /// The visitor's convex part that overlaps the sensor.
+    pub visitor_part: PartId,
+}
+
+/// A change in what overlaps a sensor, recorded by `Scene::step`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SensorEvent {
+    /// A part started overlapping a sensor during the step.
+    Begin(SensorOverlap),
+    /// A part stopped overlapping a sensor during the step, including because one of the bodies
+    /// was removed from the scene.
+    End(SensorOverlap),
+}
@@ -0,0 +1,200 @@
+use nalgebra::{Point2, Vector2};
+
+use crate::constants::{GJK_MAX_ITERATIONS, LINEAR_SLOP};
+use crate::fixture::PartId;
+use crate::gjk::{Proxy, Simplex, SimplexVertex};
+use crate::scene::BodyHandle;
+
+/// Where a shape swept through a `Scene` first touched a body.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShapeCastHit {
+    /// The body that was hit.
+    pub handle: BodyHandle,
+    /// The convex part of the body that was hit.
+    pub part: PartId,
+    /// How far along the translation the shape touches the body, from 0 to 1.
+    pub toi: f64,
+    /// The contact point on the surface of the body, in world space.
+    pub point: Point2<f64>,
+    /// The surface normal of the body at the contact point, pointing towards the swept shape.
+    pub normal: Vector2<f64>,
+}
+
+// Adapted from Box2D's b2ShapeCast: https://github.com/erincatto/box2d
+/// Sweeps one convex part along a translation towards another using the GJK ray cast.
+///
+/// The sweep stops just short of contact, within `LINEAR_SLOP`. Parts that already touch at the
+/// start are not reported.
+///
+/// # Arguments
+///
+/// * `a` - The part that stays in place.
+/// * `b` - The part that moves.
+/// * `translation` - How far `b` moves.
+///
+/// # Returns
+///
+/// The time of impact as a fraction of the translation, along with the contact point on `a` and
+/// the normal of `a` pointing towards `b`, or `None` if they do not touch.
+pub(crate) fn shape_cast(
+    a: &Proxy,
+    b: &Proxy,
+    translation: &Vector2<f64>,
+) -> Option<(f64, Point2<f64>, Vector2<f64>)> {
+    let radius = a.radius() + b.radius();
+    let r = translation;
+
+    // Stop the sweep when the cores are this far apart, keeping a small gap so that GJK never
+    // has to deal with touching cores
+    let target = (radius - LINEAR_SLOP).max(LINEAR_SLOP);
+    let tolerance = 0.5 * LINEAR_SLOP;
+
+    let mut lambda = 0.0;
+    let mut normal = Vector2::zeros();
+    let mut simplex = Simplex::default();
+
+    // The simplex is built in the space of `a` minus `b` moved by `lambda * r`
+    let mut v = a.core_support(&-r) - b.core_support(r);
+    let mut iterations = 0;
+
+    while iterations < GJK_MAX_ITERATIONS && v.norm() > target + tolerance {
+        let w_a = a.core_support(&-v);
+        let w_b = b.core_support(&v);
+        let p = w_a - w_b;
+        v.normalize_mut();
+
+        // Advance along the ray if this support plane still separates the cores
+        let vp = v.dot(&p);
+        let vr = v.dot(r);
+        if vp - target > lambda * vr {
+            if vr <= 0.0 {
+                return None;
+            }
+
+            lambda = (vp - target) / vr;
+            if lambda > 1.0 {
+                return None;
+            }
+
+            normal = -v;
+            simplex.count = 0;
+        }
+
+        simplex.push(SimplexVertex::new(w_b + lambda * r, w_a));
+        simplex.solve();
+
+        if simplex.count == 3 {
+            // The cores overlap
+            return None;
+        }
+
+        v = simplex.closest_point();
+        iterations += 1;
+    }
+
+    // Already touching at the start, so there is no time of impact to report
+    if lambda == 0.0 {
+        return None;
+    }
+
+    let (_, point_a) = simplex.witness_points();
+    if v.norm_squared() > 0.0 {
+        normal = -v.normalize();
+    }
+
+    Some((lambda, a.surface_point(&point_a, &normal), normal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::circle::Circle;
+    use crate::polygon::Polygon;
+    use crate::shapes::ConvexPart;
+    use crate::transform::Transform;
+
+    fn unit_square() -> Polygon {
+        Polygon::new(vec![
+            Point2::new(-0.5, -0.5),
+            Point2::new(0.5, -0.5),
+            Point2::new(0.5, 0.5),
+            Point2::new(-0.5, 0.5),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_circle_against_circle() {
+        let big = Circle::new(1.0).unwrap();
+        let small = Circle::new(0.5).unwrap();
+        let a = Proxy {
+            part: ConvexPart::Circle(&big),
+            tx: Transform::new(Point2::new(5.0, 0.0)),
+        };
+        let b = Proxy {
+            part: ConvexPart::Circle(&small),
+            tx: Transform::default(),
+        };
+
+        // Touches once the centers are 1.5 apart, after moving 3.5 of 10
+        let (toi, point, normal) = shape_cast(&a, &b, &Vector2::new(10.0, 0.0)).unwrap();
+        assert!((toi - 0.35).abs() < 1e-3);
+        assert!((point - Point2::new(4.0, 0.0)).norm() < 1e-2);
+        assert!((normal - Vector2::new(-1.0, 0.0)).norm() < 1e-9);
+
+        assert!(shape_cast(&a, &b, &Vector2::new(3.0, 0.0)).is_none());
+        assert!(shape_cast(&a, &b, &Vector2::new(-10.0, 0.0)).is_none());
+        assert!(shape_cast(&a, &b, &Vector2::new(10.0, 10.0)).is_none());
+    }
+
+    #[test]
+    fn test_polygon_against_rotated_polygon() {
+        let square = unit_square();
+        // Standing on a corner, so the lowest point is sqrt(0.5) below the center
+        let a = Proxy {
+            part: ConvexPart::Polygon(&square),
+            tx: Transform::with_orientation(Point2::new(0.0, 3.0), std::f64::consts::FRAC_PI_4),
+        };
+        let b = Proxy {
+            part: ConvexPart::Polygon(&square),
+            tx: Transform::default(),
+        };
+
+        let (toi, point, normal) = shape_cast(&a, &b, &Vector2::new(0.0, 4.0)).unwrap();
+        let expected = (3.0 - 0.5f64.sqrt() - 0.5) / 4.0;
+        assert!((toi - expected).abs() < 1e-2);
+        assert!((point - Point2::new(0.0, 3.0 - 0.5f64.sqrt())).norm() < 1e-2);
+        assert!(normal.y < -0.7);
+
+        // Passing beside it
+        let b = Proxy {
+            tx: Transform::new(Point2::new(2.0, 0.0)),
+            ..b
+        };
+        assert!(shape_cast(&a, &b, &Vector2::new(0.0, 4.0)).is_none());
+    }
+
+    #[test]
+    fn test_starting_overlap_is_ignored() {
+        let square = unit_square();
+        let circle = Circle::new(0.5).unwrap();
+        let a = Proxy {
+            part: ConvexPart::Polygon(&square),
+            tx: Transform::default(),
+        };
+        let b = Proxy {
+            part: ConvexPart::Circle(&circle),
+            tx: Transform::new(Point2::new(0.6, 0.0)),
+        };
+
+        assert!(shape_cast(&a, &b, &Vector2::new(-5.0, 0.0)).is_none());
+
+        // Cores overlapping as well
+        let b = Proxy {
+            tx: Transform::new(Point2::new(0.2, 0.0)),
+            ..b
+        };
+        assert!(shape_cast(&a, &b, &Vector2::new(-5.0, 0.0)).is_none());
+    }
+}
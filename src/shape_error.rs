@@ -0,0 +1,86 @@
+use std::error::Error;
+use std::fmt;
+
+/// Describes why a shape could not be constructed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ShapeError {
+    /// A polygon needs at least three vertices.
+    TooFewVertices {
+        /// The number of vertices given.
+        count: usize,
+    },
+    /// A polygon vertex has a NaN or infinite coordinate.
+    NonFiniteVertex {
+        /// The index of the offending vertex.
+        index: usize,
+    },
+    /// Two polygon vertices are at the same position.
+    DuplicateVertices {
+        /// The index of the first vertex.
+        first: usize,
+        /// The index of the second vertex.
+        second: usize,
+    },
+    /// A polygon vertex lies on the line through its neighbours.
+    CollinearVertices {
+        /// The index of the middle vertex.
+        index: usize,
+    },
+    /// The polygon encloses no area.
+    ZeroArea,
+    /// Two non-adjacent polygon edges cross each other.
+    SelfIntersecting {
+        /// The index of the first edge, which starts at the vertex with the same index.
+        first_edge: usize,
+        /// The index of the second edge.
+        second_edge: usize,
+    },
+    /// A polygon given as convex has a vertex where it turns inwards.
+    NotConvex {
+        /// The index of the reflex vertex, after the vertices were put in counter-clockwise order.
+        index: usize,
+    },
+    /// A circle radius is zero or negative.
+    NonPositiveRadius(f64),
+    /// A circle radius is NaN or infinite.
+    NonFiniteRadius(f64),
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShapeError::TooFewVertices { count } => {
+                write!(f, "a polygon needs at least 3 vertices, got {count}")
+            }
+            ShapeError::NonFiniteVertex { index } => {
+                write!(f, "polygon vertex {index} is not finite")
+            }
+            ShapeError::DuplicateVertices { first, second } => {
+                write!(
+                    f,
+                    "polygon vertices {first} and {second} are at the same position"
+                )
+            }
+            ShapeError::CollinearVertices { index } => {
+                write!(f, "polygon vertex {index} is collinear with its neighbours")
+            }
+            ShapeError::ZeroArea => write!(f, "polygon has zero area"),
+            ShapeError::SelfIntersecting {
+                first_edge,
+                second_edge,
+            } => write!(
+                f,
+                "polygon edges {first_edge} and {second_edge} intersect each other"
+            ),
+            ShapeError::NotConvex { index } => {
+                write!(f, "polygon is not convex at vertex {index}")
+            }
+            ShapeError::NonPositiveRadius(r) => {
+                write!(f, "circle radius must be positive, got {r}")
+            }
+            ShapeError::NonFiniteRadius(r) => write!(f, "circle radius must be finite, got {r}"),
+        }
+    }
+}
+
+impl Error for ShapeError {}
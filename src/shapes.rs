@@ -1,14 +1,28 @@
+#[cfg(feature = "render-piston")]
 use graphics::Context;
+#[cfg(feature = "render-piston")]
 use opengl_graphics::GlGraphics;
 
+use nalgebra::{Point2, Vector2};
+
+use crate::aabb::Aabb;
 use crate::circle::Circle;
+use crate::concave_polygon::ConcavePolygon;
+use crate::constants::GEOMETRY_TOLERANCE;
+use crate::gjk::gjk;
 use crate::mass_data::MassData;
 use crate::polygon::Polygon;
+use crate::ray::Ray;
+use crate::support_map::SupportMap;
 use crate::transform::Transform;
 use crate::types::KilogramPerCubicMeter;
 
 /// A trait representing a geometric shape with mass properties.
-pub trait Shape {
+///
+/// Implementing it outside the crate and wrapping the shape in `Shapes::Custom` adds a new
+/// primitive to a `Scene`. Such shapes are treated as convex, colliding through their support
+/// function with the generic GJK/EPA path.
+pub trait Shape: SupportMap {
     /// Calculates the mass data of the shape based on the given density.
     ///
     /// # Arguments
@@ -16,14 +30,47 @@ pub trait Shape {
     /// * `density` - The density of the material the shape is composed of.
     fn calculate_mass_data(&mut self, density: KilogramPerCubicMeter) -> MassData;
 
+    /// Returns the centroid of the shape in model space.
+    ///
+    /// This is the point `calculate_mass_data` moves to the origin.
+    fn centroid(&self) -> Point2<f64>;
+
+    /// Calculates the world space bounding box of the shape.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transform that brings the shape into world space.
+    fn bounding_box(&self, tx: &Transform) -> Aabb;
+
+    /// Finds where a ray first enters the shape.
+    ///
+    /// A ray starting inside the shape does not hit it.
+    ///
+    /// # Arguments
+    ///
+    /// * `ray` - The ray, in world space.
+    /// * `tx` - The transform that brings the shape into world space.
+    ///
+    /// # Returns
+    ///
+    /// The distance along the ray and the world space normal at the hit point, or `None` if the
+    /// ray misses within its maximum distance.
+    fn ray_cast(&self, ray: &Ray, tx: &Transform) -> Option<(f64, Vector2<f64>)>;
+
     /// Draws the shape on the screen.
     ///
+    /// Does nothing unless overridden, so that shapes implemented outside the crate keep
+    /// compiling whether or not the `render-piston` feature is enabled.
+    ///
     /// # Arguments
     ///
     /// * `c` - The graphics context.
     /// * `gl` - The OpenGL graphics object.
     /// * `tx` - The transformation to apply to the shape.
-    fn draw(&self, c: Context, gl: &mut GlGraphics, tx: &Transform);
+    #[cfg(feature = "render-piston")]
+    fn draw(&self, c: Context, gl: &mut GlGraphics, tx: &Transform) {
+        let _ = (c, gl, tx);
+    }
 
     /// Retrieves the discriminant of the shape, indicating its type.
     fn discriminant(&self) -> ShapeDiscriminant;
@@ -35,6 +82,92 @@ pub enum Shapes {
     Circle(Circle),
     /// A polygon shape.
     Polygon(Polygon),
+    /// A concave polygon, stored as convex pieces.
+    ConcavePolygon(ConcavePolygon),
+    /// A user-defined convex shape.
+    Custom(Box<dyn Shape + Send>),
+}
+
+/// A convex part of a shape.
+///
+/// The narrow phase only handles convex shapes, so it works on one pair of parts at a time.
+#[derive(Clone, Copy)]
+pub enum ConvexPart<'a> {
+    /// A circle part.
+    Circle(&'a Circle),
+    /// A convex polygon part.
+    Polygon(&'a Polygon),
+    /// A user-defined convex shape.
+    Custom(&'a dyn Shape),
+}
+
+impl Shapes {
+    /// Returns the number of convex parts the shape is made of.
+    pub fn part_count(&self) -> usize {
+        match self {
+            Shapes::Circle(_) | Shapes::Polygon(_) | Shapes::Custom(_) => 1,
+            Shapes::ConcavePolygon(cp) => cp.pieces.len(),
+        }
+    }
+
+    /// Returns one of the convex parts of the shape.
+    ///
+    /// # Arguments
+    ///
+    /// * `idx` - The index of the part, below `part_count()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of range.
+    pub fn part(&self, idx: usize) -> ConvexPart<'_> {
+        match self {
+            Shapes::Circle(c) if idx == 0 => ConvexPart::Circle(c),
+            Shapes::Polygon(p) if idx == 0 => ConvexPart::Polygon(p),
+            Shapes::ConcavePolygon(cp) => ConvexPart::Polygon(&cp.pieces[idx]),
+            Shapes::Custom(s) if idx == 0 => ConvexPart::Custom(s.as_ref()),
+            _ => panic!("shape part {idx} out of range"),
+        }
+    }
+}
+
+impl ConvexPart<'_> {
+    /// Returns `true` if a world space point lies inside or on the part.
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - The point, in world space.
+    /// * `tx` - The transform that brings the part into world space.
+    pub fn contains_point(&self, p: &Point2<f64>, tx: &Transform) -> bool {
+        match self {
+            ConvexPart::Circle(c) => c.contains_point(p, tx),
+            ConvexPart::Polygon(poly) => poly.contains_point(p, tx),
+            ConvexPart::Custom(s) => {
+                // The point is inside when GJK cannot separate it from the shape
+                let simplex = gjk(|_| *p, |d| s.support(tx, d), &(tx.pos - p));
+
+                simplex.count == 3 || simplex.closest_point().norm() <= GEOMETRY_TOLERANCE
+            }
+        }
+    }
+
+    /// Finds where a ray first enters the part.
+    ///
+    /// # Arguments
+    ///
+    /// * `ray` - The ray, in world space.
+    /// * `tx` - The transform that brings the part into world space.
+    ///
+    /// # Returns
+    ///
+    /// The distance along the ray and the world space normal at the hit point, or `None` if the
+    /// ray misses within its maximum distance.
+    pub fn ray_cast(&self, ray: &Ray, tx: &Transform) -> Option<(f64, Vector2<f64>)> {
+        match self {
+            ConvexPart::Circle(c) => c.ray_cast(ray, tx),
+            ConvexPart::Polygon(p) => p.ray_cast(ray, tx),
+            ConvexPart::Custom(s) => s.ray_cast(ray, tx),
+        }
+    }
 }
 
 impl Shape for Shapes {
@@ -43,14 +176,49 @@ impl Shape for Shapes {
         match self {
             Shapes::Circle(c) => c.calculate_mass_data(density),
             Shapes::Polygon(p) => p.calculate_mass_data(density),
+            Shapes::ConcavePolygon(cp) => cp.calculate_mass_data(density),
+            Shapes::Custom(s) => s.calculate_mass_data(density),
+        }
+    }
+
+    /// Returns the centroid of the shape in model space.
+    fn centroid(&self) -> Point2<f64> {
+        match self {
+            Shapes::Circle(c) => c.centroid(),
+            Shapes::Polygon(p) => p.centroid(),
+            Shapes::ConcavePolygon(cp) => cp.centroid(),
+            Shapes::Custom(s) => s.centroid(),
+        }
+    }
+
+    /// Calculates the world space bounding box of the shape.
+    fn bounding_box(&self, tx: &Transform) -> Aabb {
+        match self {
+            Shapes::Circle(c) => c.bounding_box(tx),
+            Shapes::Polygon(p) => p.bounding_box(tx),
+            Shapes::ConcavePolygon(cp) => cp.bounding_box(tx),
+            Shapes::Custom(s) => s.bounding_box(tx),
+        }
+    }
+
+    /// Finds where a ray first enters the shape.
+    fn ray_cast(&self, ray: &Ray, tx: &Transform) -> Option<(f64, Vector2<f64>)> {
+        match self {
+            Shapes::Circle(c) => c.ray_cast(ray, tx),
+            Shapes::Polygon(p) => p.ray_cast(ray, tx),
+            Shapes::ConcavePolygon(cp) => cp.ray_cast(ray, tx),
+            Shapes::Custom(s) => s.ray_cast(ray, tx),
         }
     }
 
     /// Draws the shape on the screen.
+    #[cfg(feature = "render-piston")]
     fn draw(&self, c: Context, gl: &mut GlGraphics, tx: &Transform) {
         match self {
             Shapes::Circle(circ) => circ.draw(c, gl, tx),
             Shapes::Polygon(p) => p.draw(c, gl, tx),
+            Shapes::ConcavePolygon(cp) => cp.draw(c, gl, tx),
+            Shapes::Custom(s) => s.draw(c, gl, tx),
         }
     }
 
@@ -59,6 +227,8 @@ impl Shape for Shapes {
         match self {
             Shapes::Circle(c) => c.discriminant(),
             Shapes::Polygon(p) => p.discriminant(),
+            Shapes::ConcavePolygon(cp) => cp.discriminant(),
+            Shapes::Custom(s) => s.discriminant(),
         }
     }
 }
@@ -69,4 +239,8 @@ pub enum ShapeDiscriminant {
     Circle,
     /// Indicates a polygon shape.
     Polygon,
+    /// Indicates a concave polygon shape.
+    ConcavePolygon,
+    /// Indicates a user-defined shape.
+    Custom,
 }
@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+
+use crate::aabb::Aabb;
+use crate::broad_phase::BroadPhase;
+use crate::constants::MAX_GRID_CELLS_PER_BODY;
+use crate::scene::BodyHandle;
+
+/// Statistics about how a `SpatialHashGrid` bucketed its bodies, used to tune the cell size.
+///
+/// Cells that are too small spread every body over many cells, while cells that are too large
+/// put many bodies in each cell and test them all against each other.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GridStats {
+    /// The number of tracked bodies.
+    pub bodies: usize,
+    /// The number of cells holding at least one body.
+    pub occupied_cells: usize,
+    /// The largest number of bodies in a single cell.
+    pub max_bodies_per_cell: usize,
+    /// The average number of bodies in an occupied cell.
+    pub mean_bodies_per_cell: f64,
+    /// The average number of cells a body covers.
+    pub mean_cells_per_body: f64,
+    /// The number of bodies too large to bucket, which are tested against every other body.
+    pub oversized_bodies: usize,
+}
+
+/// A broad phase that buckets bodies into the cells of a uniform grid they overlap.
+///
+/// Only bodies sharing a cell are tested against each other. The cells are rebuilt on every call
+/// to `pairs`, which makes this the cheapest strategy for many small, similarly sized bodies that
+/// all move, such as sand or marbles. A cell size around the size of a typical body works best.
+/// Box queries are not sped up, as they scan every body.
+///
+/// Bodies that would cover more than `MAX_GRID_CELLS_PER_BODY` cells, such as a long static
+/// floor, are kept out of the cells and tested against every other body instead.
+pub struct SpatialHashGrid {
+    /// The width and height of a cell.
+    cell_size: f64,
+    /// The bounding box of each tracked body.
+    boxes: HashMap<BodyHandle, Aabb>,
+    /// The bodies overlapping each occupied cell, as of the last call to `pairs`.
+    cells: HashMap<(i64, i64), Vec<BodyHandle>>,
+    /// The bodies too large to bucket, as of the last call to `pairs`.
+    oversized: Vec<BodyHandle>,
+}
+
+impl SpatialHashGrid {
+    /// Creates a new, empty spatial hash grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `cell_size` - The width and height of a cell.
+    ///
+    /// # Panics
+    ///
+    /// If `cell_size` is not positive.
+    pub fn new(cell_size: f64) -> Self {
+        assert!(cell_size > 0.0, "cell size must be positive");
+
+        SpatialHashGrid {
+            cell_size,
+            boxes: HashMap::new(),
+            cells: HashMap::new(),
+            oversized: Vec::new(),
+        }
+    }
+
+    /// Returns the width and height of a cell.
+    pub fn cell_size(&self) -> f64 {
+        self.cell_size
+    }
+
+    /// Returns the coordinates of the cell containing a point.
+    fn cell(&self, x: f64, y: f64) -> (i64, i64) {
+        (
+            (x / self.cell_size).floor() as i64,
+            (y / self.cell_size).floor() as i64,
+        )
+    }
+
+    /// Returns the number of cells a box covers.
+    ///
+    /// This is worked out in floating point so that huge and infinite boxes do not overflow.
+    fn cell_count(&self, aabb: &Aabb) -> f64 {
+        let span = |min: f64, max: f64| {
+            (max / self.cell_size).floor() - (min / self.cell_size).floor() + 1.0
+        };
+
+        span(aabb.min.x, aabb.max.x) * span(aabb.min.y, aabb.max.y)
+    }
+
+    /// Returns statistics about the cells as of the last call to `pairs`.
+    pub fn stats(&self) -> GridStats {
+        let occupied_cells = self.cells.len();
+        let entries: usize = self.cells.values().map(Vec::len).sum();
+        let bodies = self.boxes.len();
+
+        GridStats {
+            bodies,
+            occupied_cells,
+            max_bodies_per_cell: self.cells.values().map(Vec::len).max().unwrap_or(0),
+            mean_bodies_per_cell: if occupied_cells == 0 {
+                0.0
+            } else {
+                entries as f64 / occupied_cells as f64
+            },
+            mean_cells_per_body: if bodies == 0 {
+                0.0
+            } else {
+                entries as f64 / bodies as f64
+            },
+            oversized_bodies: self.oversized.len(),
+        }
+    }
+}
+
+impl BroadPhase for SpatialHashGrid {
+    /// Starts tracking a body.
+    fn insert(&mut self, handle: BodyHandle, aabb: Aabb) {
+        self.boxes.insert(handle, aabb);
+    }
+
+    /// Stops tracking a body.
+    fn remove(&mut self, handle: BodyHandle) {
+        self.boxes.remove(&handle);
+    }
+
+    /// Updates the bounding box of a tracked body.
+    fn update(&mut self, handle: BodyHandle, aabb: Aabb) {
+        if let Some(old) = self.boxes.get_mut(&handle) {
+            *old = aabb;
+        }
+    }
+
+    /// Rebuilds the cells and tests the bodies within each cell against each other.
+    fn pairs(&mut self) -> Vec<(BodyHandle, BodyHandle)> {
+        // Reuse the buckets to avoid reallocating them every step
+        for bucket in self.cells.values_mut() {
+            bucket.clear();
+        }
+
+        self.oversized.clear();
+
+        for (&handle, aabb) in &self.boxes {
+            let cell_count = self.cell_count(aabb);
+
+            // A box with NaN coordinates overlaps nothing
+            if cell_count.is_nan() {
+                continue;
+            }
+
+            if cell_count > MAX_GRID_CELLS_PER_BODY as f64 {
+                self.oversized.push(handle);
+                continue;
+            }
+
+            let (min_x, min_y) = self.cell(aabb.min.x, aabb.min.y);
+            let (max_x, max_y) = self.cell(aabb.max.x, aabb.max.y);
+
+            for x in min_x..=max_x {
+                for y in min_y..=max_y {
+                    self.cells.entry((x, y)).or_default().push(handle);
+                }
+            }
+        }
+        self.cells.retain(|_, bucket| !bucket.is_empty());
+
+        let mut pairs = Vec::new();
+        for bucket in self.cells.values() {
+            for (i, a) in bucket.iter().enumerate() {
+                for b in &bucket[i + 1..] {
+                    if self.boxes[a].overlaps(&self.boxes[b]) {
+                        pairs.push(if a < b { (*a, *b) } else { (*b, *a) });
+                    }
+                }
+            }
+        }
+
+        for &big in &self.oversized {
+            for (&other, aabb) in &self.boxes {
+                if other != big && self.boxes[&big].overlaps(aabb) {
+                    pairs.push(if big < other {
+                        (big, other)
+                    } else {
+                        (other, big)
+                    });
+                }
+            }
+        }
+
+        // Bodies sharing several cells are found once per shared cell, and two oversized bodies
+        // find each other twice
+        pairs.sort();
+        pairs.dedup();
+        pairs
+    }
+
+    /// Tests the box against every tracked box, as the cells are only rebuilt by `pairs`.
+    ///
+    /// This is a linear fallback, so `Scene::query_aabb` and `Scene::ray_cast` get no help from
+    /// this strategy. Prefer a `TreeBroadPhase` when queries are frequent.
+    fn query(&self, aabb: &Aabb) -> Vec<BodyHandle> {
+        self.boxes
+            .iter()
+            .filter(|(_, other)| other.overlaps(aabb))
+            .map(|(handle, _)| *handle)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use nalgebra::Point2;
+
+    use crate::broad_phase::test_handles;
+
+    #[test]
+    fn test_pairs_are_unique() {
+        let h = test_handles(3);
+        let mut grid = SpatialHashGrid::new(1.0);
+
+        // The first two share four cells, the third shares a cell but not its box
+        grid.insert(
+            h[0],
+            Aabb::new(Point2::new(0.5, 0.5), Point2::new(2.5, 2.5)),
+        );
+        grid.insert(
+            h[1],
+            Aabb::new(Point2::new(0.5, 0.5), Point2::new(1.5, 1.5)),
+        );
+        grid.insert(
+            h[2],
+            Aabb::new(Point2::new(2.8, 2.8), Point2::new(3.5, 3.5)),
+        );
+
+        assert_eq!(grid.pairs(), vec![(h[0], h[1])]);
+
+        grid.update(
+            h[2],
+            Aabb::new(Point2::new(2.2, 2.2), Point2::new(3.5, 3.5)),
+        );
+        assert_eq!(grid.pairs(), vec![(h[0], h[1]), (h[0], h[2])]);
+
+        grid.remove(h[0]);
+        assert!(grid.pairs().is_empty());
+    }
+
+    #[test]
+    fn test_stats() {
+        let h = test_handles(2);
+        let mut grid = SpatialHashGrid::new(2.0);
+        assert_eq!(grid.stats(), GridStats::default());
+
+        // Covers cells (-1, -1) to (0, 0), then one that fits in (0, 0)
+        grid.insert(
+            h[0],
+            Aabb::new(Point2::new(-1.0, -1.0), Point2::new(1.0, 1.0)),
+        );
+        grid.insert(
+            h[1],
+            Aabb::new(Point2::new(0.5, 0.5), Point2::new(1.0, 1.0)),
+        );
+        grid.pairs();
+
+        assert_eq!(
+            grid.stats(),
+            GridStats {
+                bodies: 2,
+                occupied_cells: 4,
+                max_bodies_per_cell: 2,
+                mean_bodies_per_cell: 5.0 / 4.0,
+                mean_cells_per_body: 5.0 / 2.0,
+                oversized_bodies: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_large_bodies_skip_the_cells() {
+        let h = test_handles(4);
+        let mut grid = SpatialHashGrid::new(2.0);
+
+        // A 1000 by 1000 floor would cover 250,000 cells
+        grid.insert(
+            h[0],
+            Aabb::new(Point2::new(-500.0, 0.0), Point2::new(500.0, 1000.0)),
+        );
+        grid.insert(
+            h[1],
+            Aabb::new(Point2::new(0.5, -1.0), Point2::new(1.5, 0.5)),
+        );
+        grid.insert(
+            h[2],
+            Aabb::new(
+                Point2::new(f64::NEG_INFINITY, -2.0),
+                Point2::new(f64::INFINITY, -0.5),
+            ),
+        );
+        grid.insert(
+            h[3],
+            Aabb::new(Point2::new(f64::NAN, 0.0), Point2::new(1.0, 1.0)),
+        );
+
+        assert_eq!(grid.pairs(), vec![(h[0], h[1]), (h[1], h[2])]);
+
+        let stats = grid.stats();
+        assert_eq!(stats.oversized_bodies, 2);
+        assert_eq!(stats.occupied_cells, 2);
+    }
+}
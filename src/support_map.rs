@@ -0,0 +1,212 @@
+use nalgebra::{Point2, Rotation2, Vector2};
+
+use crate::circle::Circle;
+use crate::concave_polygon::ConcavePolygon;
+use crate::constants::{GEOMETRY_TOLERANCE, SUPPORT_EDGE_ANGLE};
+use crate::polygon::Polygon;
+use crate::shapes::{ConvexPart, Shapes};
+use crate::transform::Transform;
+
+/// A convex shape described by its support function, which is all the generic GJK/EPA collision
+/// path needs to know about it.
+pub trait SupportMap {
+    /// Finds the point of the shape furthest along a direction.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The direction, in model space. It does not need to be normalized.
+    ///
+    /// # Returns
+    ///
+    /// The support point, in model space.
+    fn local_support(&self, dir: &Vector2<f64>) -> Point2<f64>;
+
+    /// Finds the edge of the shape that faces a direction the most, for building contacts.
+    ///
+    /// The default implementation compares the support points in two directions just either
+    /// side of `dir`, which finds edges that are almost perpendicular to it. Shapes with curved
+    /// boundaries should return `None`.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The direction, in model space. It does not need to be normalized.
+    ///
+    /// # Returns
+    ///
+    /// The endpoints of the edge counter-clockwise, in model space, or `None` if the shape only
+    /// touches a single point in that direction.
+    fn local_support_edge(&self, dir: &Vector2<f64>) -> Option<(Point2<f64>, Point2<f64>)> {
+        let p1 = self.local_support(&(Rotation2::new(-SUPPORT_EDGE_ANGLE) * dir));
+        let p2 = self.local_support(&(Rotation2::new(SUPPORT_EDGE_ANGLE) * dir));
+
+        ((p2 - p1).norm() > GEOMETRY_TOLERANCE).then_some((p1, p2))
+    }
+
+    /// Finds the point of the shape furthest along a world space direction.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transform that brings the shape into world space.
+    /// * `dir` - The direction, in world space.
+    ///
+    /// # Returns
+    ///
+    /// The support point, in world space.
+    fn support(&self, tx: &Transform, dir: &Vector2<f64>) -> Point2<f64> {
+        tx.to_world(&self.local_support(&tx.rotate_to_local(dir)))
+    }
+
+    /// Finds the edge of the shape that faces a world space direction the most.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transform that brings the shape into world space.
+    /// * `dir` - The direction, in world space.
+    ///
+    /// # Returns
+    ///
+    /// The endpoints of the edge counter-clockwise, in world space, or `None` if the shape only
+    /// touches a single point in that direction.
+    fn support_edge(
+        &self,
+        tx: &Transform,
+        dir: &Vector2<f64>,
+    ) -> Option<(Point2<f64>, Point2<f64>)> {
+        self.local_support_edge(&tx.rotate_to_local(dir))
+            .map(|(p1, p2)| (tx.to_world(&p1), tx.to_world(&p2)))
+    }
+}
+
+impl SupportMap for Circle {
+    /// Finds the point of the circle furthest along a direction.
+    fn local_support(&self, dir: &Vector2<f64>) -> Point2<f64> {
+        self.find_support(dir)
+    }
+
+    /// Returns `None`, as a circle has no edges.
+    fn local_support_edge(&self, _dir: &Vector2<f64>) -> Option<(Point2<f64>, Point2<f64>)> {
+        None
+    }
+}
+
+impl SupportMap for Polygon {
+    /// Finds the vertex of the polygon furthest along a direction.
+    fn local_support(&self, dir: &Vector2<f64>) -> Point2<f64> {
+        self.find_support(dir)
+    }
+
+    /// Returns the edge whose normal is closest to the direction.
+    fn local_support_edge(&self, dir: &Vector2<f64>) -> Option<(Point2<f64>, Point2<f64>)> {
+        let (i, _) = self
+            .normals
+            .iter()
+            .enumerate()
+            .max_by(|(_, n1), (_, n2)| n1.dot(dir).total_cmp(&n2.dot(dir)))?;
+
+        Some((
+            self.vertices[i],
+            self.vertices[(i + 1) % self.vertices.len()],
+        ))
+    }
+}
+
+impl SupportMap for ConcavePolygon {
+    /// Finds the vertex of the outline furthest along a direction, which makes this the support
+    /// function of the outline's convex hull.
+    fn local_support(&self, dir: &Vector2<f64>) -> Point2<f64> {
+        self.pieces
+            .iter()
+            .map(|piece| piece.find_support(dir))
+            .max_by(|p1, p2| p1.coords.dot(dir).total_cmp(&p2.coords.dot(dir)))
+            .expect("a concave polygon has at least one piece")
+    }
+}
+
+impl SupportMap for Shapes {
+    /// Dispatches to the shape.
+    fn local_support(&self, dir: &Vector2<f64>) -> Point2<f64> {
+        match self {
+            Shapes::Circle(c) => c.local_support(dir),
+            Shapes::Polygon(p) => p.local_support(dir),
+            Shapes::ConcavePolygon(cp) => cp.local_support(dir),
+            Shapes::Custom(s) => s.local_support(dir),
+        }
+    }
+
+    /// Dispatches to the shape.
+    fn local_support_edge(&self, dir: &Vector2<f64>) -> Option<(Point2<f64>, Point2<f64>)> {
+        match self {
+            Shapes::Circle(c) => c.local_support_edge(dir),
+            Shapes::Polygon(p) => p.local_support_edge(dir),
+            Shapes::ConcavePolygon(cp) => cp.local_support_edge(dir),
+            Shapes::Custom(s) => s.local_support_edge(dir),
+        }
+    }
+}
+
+impl SupportMap for ConvexPart<'_> {
+    /// Dispatches to the part's shape.
+    fn local_support(&self, dir: &Vector2<f64>) -> Point2<f64> {
+        match self {
+            ConvexPart::Circle(c) => c.local_support(dir),
+            ConvexPart::Polygon(p) => p.local_support(dir),
+            ConvexPart::Custom(s) => s.local_support(dir),
+        }
+    }
+
+    /// Dispatches to the part's shape.
+    fn local_support_edge(&self, dir: &Vector2<f64>) -> Option<(Point2<f64>, Point2<f64>)> {
+        match self {
+            ConvexPart::Circle(c) => c.local_support_edge(dir),
+            ConvexPart::Polygon(p) => p.local_support_edge(dir),
+            ConvexPart::Custom(s) => s.local_support_edge(dir),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A square known only through its support function, to exercise the default edge search.
+    struct SupportOnlySquare;
+
+    impl SupportMap for SupportOnlySquare {
+        fn local_support(&self, dir: &Vector2<f64>) -> Point2<f64> {
+            Point2::new(dir.x.signum(), dir.y.signum())
+        }
+    }
+
+    #[test]
+    fn test_default_support_edge() {
+        let square = SupportOnlySquare;
+
+        assert_eq!(
+            square.local_support_edge(&Vector2::new(1.0, 0.0)),
+            Some((Point2::new(1.0, -1.0), Point2::new(1.0, 1.0)))
+        );
+        assert_eq!(square.local_support_edge(&Vector2::new(1.0, 1.0)), None);
+    }
+
+    #[test]
+    fn test_world_support() {
+        let poly = Polygon::new(vec![
+            Point2::new(-1.0, -1.0),
+            Point2::new(1.0, -1.0),
+            Point2::new(1.0, 1.0),
+            Point2::new(-1.0, 1.0),
+        ])
+        .unwrap();
+        let tx = Transform::with_orientation(Point2::new(5.0, 0.0), std::f64::consts::FRAC_PI_2);
+
+        // Turned a quarter, the model's bottom edge now faces right
+        let (p1, p2) = poly.support_edge(&tx, &Vector2::new(1.0, 0.0)).unwrap();
+        assert!((p1 - Point2::new(6.0, -1.0)).norm() < 1e-12);
+        assert!((p2 - Point2::new(6.0, 1.0)).norm() < 1e-12);
+
+        let circle = Circle::new(2.0).unwrap();
+        let p = circle.support(&tx, &Vector2::new(0.0, -3.0));
+        assert!((p - Point2::new(5.0, -2.0)).norm() < 1e-12);
+        assert!(circle.support_edge(&tx, &Vector2::x()).is_none());
+    }
+}
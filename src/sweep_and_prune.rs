@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use crate::aabb::Aabb;
+use crate::broad_phase::BroadPhase;
+use crate::scene::BodyHandle;
+
+/// The world axis a `SweepAndPrune` sorts along.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    /// The horizontal axis.
+    X,
+    /// The vertical axis.
+    Y,
+}
+
+/// A broad phase that sorts bounding boxes along one axis and sweeps over them.
+///
+/// Between steps bodies rarely change order much, so the list is kept sorted with an insertion
+/// sort, which is close to linear on nearly sorted input. This works best when the bodies are
+/// spread out along the chosen axis, such as in a long horizontal level. Box queries are not
+/// sped up, as they scan every body.
+pub struct SweepAndPrune {
+    /// The axis the boxes are sorted along.
+    axis: Axis,
+    /// The tracked bodies and their boxes, sorted by the lower bound on the axis as of the last
+    /// call to `pairs`.
+    entries: Vec<(BodyHandle, Aabb)>,
+    /// The position of each tracked body in `entries`.
+    positions: HashMap<BodyHandle, usize>,
+}
+
+impl SweepAndPrune {
+    /// Creates a new, empty sweep and prune broad phase.
+    ///
+    /// # Arguments
+    ///
+    /// * `axis` - The axis to sort along, ideally the one the bodies are most spread out on.
+    pub fn new(axis: Axis) -> Self {
+        SweepAndPrune {
+            axis,
+            entries: Vec::new(),
+            positions: HashMap::new(),
+        }
+    }
+
+    /// Returns the lower and upper bound of a box on the sorting axis.
+    fn bounds(&self, aabb: &Aabb) -> (f64, f64) {
+        match self.axis {
+            Axis::X => (aabb.min.x, aabb.max.x),
+            Axis::Y => (aabb.min.y, aabb.max.y),
+        }
+    }
+
+    /// Restores the order of `entries` after boxes have moved.
+    fn insertion_sort(&mut self) {
+        for i in 1..self.entries.len() {
+            let mut j = i;
+
+            while j > 0 && self.bounds(&self.entries[j - 1].1).0 > self.bounds(&self.entries[j].1).0
+            {
+                self.entries.swap(j - 1, j);
+                self.positions.insert(self.entries[j - 1].0, j - 1);
+                self.positions.insert(self.entries[j].0, j);
+                j -= 1;
+            }
+        }
+    }
+}
+
+impl Default for SweepAndPrune {
+    /// Returns an empty sweep and prune broad phase that sorts along the x axis.
+    fn default() -> Self {
+        Self::new(Axis::X)
+    }
+}
+
+impl BroadPhase for SweepAndPrune {
+    /// Starts tracking a body, appending it to be sorted in on the next sweep.
+    fn insert(&mut self, handle: BodyHandle, aabb: Aabb) {
+        if let Some(&i) = self.positions.get(&handle) {
+            self.entries[i].1 = aabb;
+            return;
+        }
+
+        self.positions.insert(handle, self.entries.len());
+        self.entries.push((handle, aabb));
+    }
+
+    /// Stops tracking a body.
+    fn remove(&mut self, handle: BodyHandle) {
+        let Some(i) = self.positions.remove(&handle) else {
+            return;
+        };
+
+        self.entries.remove(i);
+        for (j, (h, _)) in self.entries.iter().enumerate().skip(i) {
+            self.positions.insert(*h, j);
+        }
+    }
+
+    /// Updates the bounding box of a tracked body.
+    fn update(&mut self, handle: BodyHandle, aabb: Aabb) {
+        if let Some(&i) = self.positions.get(&handle) {
+            self.entries[i].1 = aabb;
+        }
+    }
+
+    /// Re-sorts the boxes and sweeps along the axis, testing the other axis for every pair that
+    /// overlaps on this one.
+    fn pairs(&mut self) -> Vec<(BodyHandle, BodyHandle)> {
+        self.insertion_sort();
+
+        let mut pairs = Vec::new();
+        for (i, (ha, a)) in self.entries.iter().enumerate() {
+            let (_, a_max) = self.bounds(a);
+
+            for (hb, b) in &self.entries[i + 1..] {
+                // Everything further along starts after this box ends
+                if self.bounds(b).0 > a_max {
+                    break;
+                }
+
+                if a.overlaps(b) {
+                    pairs.push(if ha < hb { (*ha, *hb) } else { (*hb, *ha) });
+                }
+            }
+        }
+
+        pairs.sort();
+        pairs
+    }
+
+    /// Tests the box against every tracked box, as the boxes may have moved out of order since
+    /// the last sweep.
+    ///
+    /// This is a linear fallback, so `Scene::query_aabb` and `Scene::ray_cast` get no help from
+    /// this strategy. Prefer a `TreeBroadPhase` when queries are frequent.
+    fn query(&self, aabb: &Aabb) -> Vec<BodyHandle> {
+        self.entries
+            .iter()
+            .filter(|(_, other)| other.overlaps(aabb))
+            .map(|(handle, _)| *handle)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use nalgebra::Point2;
+
+    use crate::broad_phase::test_handles;
+
+    /// Returns a unit box with its lower left corner at the given coordinates.
+    fn unit_box(x: f64, y: f64) -> Aabb {
+        Aabb::new(Point2::new(x, y), Point2::new(x + 1.0, y + 1.0))
+    }
+
+    #[test]
+    fn test_pairs_follow_moving_boxes() {
+        let handles = test_handles(3);
+        let (a, b, c) = (handles[0], handles[1], handles[2]);
+
+        let mut sap = SweepAndPrune::default();
+        sap.insert(a, unit_box(0.0, 0.0));
+        sap.insert(b, unit_box(0.5, 0.5));
+        sap.insert(c, unit_box(5.0, 0.0));
+        assert_eq!(sap.pairs(), vec![(a, b)]);
+
+        // Swap the order along x and separate the first pair on y only
+        sap.update(a, unit_box(5.5, 0.5));
+        sap.update(b, unit_box(0.5, 3.0));
+        assert_eq!(sap.pairs(), vec![(a, c)]);
+        assert_eq!(sap.positions[&c], 1);
+        assert_eq!(sap.positions[&a], 2);
+
+        sap.remove(c);
+        assert!(sap.pairs().is_empty());
+        assert_eq!(sap.positions[&a], 1);
+    }
+}
@@ -1,20 +1,22 @@
-use nalgebra::Point2;
+use nalgebra::{Point2, Rotation2, Vector2};
 
 use crate::types::Radian;
 
-/// Represents the transformation (position and orientation) of an object in 2D space.
-
+/// Represents the transformation (position and rotation) of an object in 2D space.
+///
+/// This is the single source of truth for a body's orientation; shapes store their geometry in
+/// model space and are brought into world space through it.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Transform {
     /// The position of the object.
     pub pos: Point2<f64>,
-    // rot: Rotation2<f64>,
+    /// The rotation of the object.
+    pub rot: Rotation2<f64>,
     // scale: Vector2<f64>,
-    /// The orientation (rotation) of the object in radians.
-    pub orientation: Radian,
 }
 
 impl Transform {
-    /// Creates a new `Transform` with the specified position.
+    /// Creates a new `Transform` with the specified position and no rotation.
     ///
     /// # Arguments
     ///
@@ -22,9 +24,93 @@ impl Transform {
     pub fn new(pos: Point2<f64>) -> Self {
         Transform {
             pos,
-            // rot: Rotation2::identity(),
+            rot: Rotation2::identity(),
             // scale: Vector2::new(1.0, 1.0),
-            orientation: 0.0,
         }
     }
+
+    /// Creates a new `Transform` with the specified position and orientation.
+    ///
+    /// # Arguments
+    ///
+    /// * `pos` - The position of the object.
+    /// * `orientation` - The orientation of the object in radians.
+    pub fn with_orientation(pos: Point2<f64>, orientation: Radian) -> Self {
+        Transform {
+            pos,
+            rot: Rotation2::new(orientation),
+        }
+    }
+
+    /// Returns the orientation of the object in radians, in the range `[-pi, pi]`.
+    pub fn orientation(&self) -> Radian {
+        self.rot.angle()
+    }
+
+    /// Rotates the object by the given angle.
+    ///
+    /// The rotation is rebuilt from the summed angle so that repeated small steps do not let the
+    /// matrix drift away from orthonormal.
+    ///
+    /// # Arguments
+    ///
+    /// * `delta` - The angle to rotate by in radians.
+    pub fn rotate(&mut self, delta: Radian) {
+        self.rot = Rotation2::new(self.rot.angle() + delta);
+    }
+
+    /// Places a transform given relative to this one into the same space as this one.
+    ///
+    /// # Arguments
+    ///
+    /// * `local` - The transform relative to this one, such as a fixture's placement on its body.
+    pub fn compose(&self, local: &Transform) -> Transform {
+        Transform {
+            pos: self.to_world(&local.pos),
+            rot: self.rot * local.rot,
+        }
+    }
+
+    /// Transforms a point from model space into world space.
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - The point in model space.
+    pub fn to_world(&self, p: &Point2<f64>) -> Point2<f64> {
+        self.rot * p + self.pos.coords
+    }
+
+    /// Transforms a point from world space into model space.
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - The point in world space.
+    pub fn to_local(&self, p: &Point2<f64>) -> Point2<f64> {
+        self.rot.inverse() * (p - self.pos.coords)
+    }
+
+    /// Rotates a direction from model space into world space.
+    ///
+    /// # Arguments
+    ///
+    /// * `v` - The direction in model space.
+    pub fn rotate_to_world(&self, v: &Vector2<f64>) -> Vector2<f64> {
+        self.rot * v
+    }
+
+    /// Rotates a direction from world space into model space.
+    ///
+    /// # Arguments
+    ///
+    /// * `v` - The direction in world space.
+    pub fn rotate_to_local(&self, v: &Vector2<f64>) -> Vector2<f64> {
+        self.rot.inverse() * v
+    }
+}
+
+impl Default for Transform {
+    /// Returns a transform at the origin with no rotation.
+    fn default() -> Self {
+        Transform::new(Point2::origin())
+    }
 }
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use crate::aabb::Aabb;
+use crate::broad_phase::BroadPhase;
+use crate::constants::AABB_MARGIN;
+use crate::dynamic_tree::{DynamicTree, ProxyId};
+use crate::scene::BodyHandle;
+
+/// A broad phase backed by a `DynamicTree` of fattened bounding boxes.
+///
+/// This is the scene's default, as it copes well with bodies spread out in any direction.
+pub struct TreeBroadPhase {
+    /// The tree over the bodies' fattened bounding boxes.
+    tree: DynamicTree<BodyHandle>,
+    /// The tree leaf of each tracked body.
+    proxies: HashMap<BodyHandle, ProxyId>,
+    /// How far each bounding box is grown on every side.
+    margin: f64,
+}
+
+impl TreeBroadPhase {
+    /// Creates a new, empty tree broad phase.
+    ///
+    /// # Arguments
+    ///
+    /// * `margin` - How far to grow each bounding box on every side. Larger margins mean fewer
+    ///   tree updates for moving bodies, but more candidate pairs.
+    pub fn new(margin: f64) -> Self {
+        TreeBroadPhase {
+            tree: DynamicTree::new(),
+            proxies: HashMap::new(),
+            margin,
+        }
+    }
+}
+
+impl Default for TreeBroadPhase {
+    /// Returns an empty tree broad phase with a margin of `AABB_MARGIN`.
+    fn default() -> Self {
+        Self::new(AABB_MARGIN)
+    }
+}
+
+impl BroadPhase for TreeBroadPhase {
+    /// Starts tracking a body by adding a leaf for it to the tree.
+    fn insert(&mut self, handle: BodyHandle, aabb: Aabb) {
+        let proxy = self.tree.create_proxy(aabb, self.margin, handle);
+
+        if let Some(old) = self.proxies.insert(handle, proxy) {
+            self.tree.destroy_proxy(old);
+        }
+    }
+
+    /// Stops tracking a body.
+    fn remove(&mut self, handle: BodyHandle) {
+        if let Some(proxy) = self.proxies.remove(&handle) {
+            self.tree.destroy_proxy(proxy);
+        }
+    }
+
+    /// Updates the bounding box of a tracked body, moving its leaf only if it left its fattened
+    /// box.
+    fn update(&mut self, handle: BodyHandle, aabb: Aabb) {
+        if let Some(&proxy) = self.proxies.get(&handle) {
+            self.tree.move_proxy(proxy, aabb, self.margin);
+        }
+    }
+
+    /// Queries the tree with every body's fattened box.
+    fn pairs(&mut self) -> Vec<(BodyHandle, BodyHandle)> {
+        let mut pairs = Vec::new();
+
+        for (&handle, &proxy) in &self.proxies {
+            self.tree.query(self.tree.fat_aabb(proxy), |other| {
+                let other = *self.tree.data(other);
+
+                // Each overlap is found from both sides, so only keep one
+                if handle < other {
+                    pairs.push((handle, other));
+                }
+            });
+        }
+
+        pairs.sort();
+        pairs
+    }
+
+    /// Walks the tree down to the leaves whose fattened boxes overlap the box.
+    fn query(&self, aabb: &Aabb) -> Vec<BodyHandle> {
+        let mut handles = Vec::new();
+        self.tree
+            .query(aabb, |proxy| handles.push(*self.tree.data(proxy)));
+
+        handles
+    }
+}
@@ -0,0 +1,49 @@
+use nalgebra::Vector2;
+
+use crate::constants::{GRAVITY, PEN_ALLOWANCE, PERCENT_CORRECTION, VELOCITY_ITERATIONS};
+use crate::types::{MeterPerSec, MeterPerSquaredSecond, NormalizedCoefficient};
+
+/// Tunable parameters of a `Scene` that used to be compile-time constants.
+///
+/// The scene reads these every step, so they can be changed between steps.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorldSettings {
+    /// Acceleration applied to every dynamic body.
+    pub gravity: Vector2<MeterPerSquaredSecond>,
+    /// Number of times the contact impulses are iterated per step.
+    pub velocity_iterations: usize,
+    /// Penetration allowed before positional correction kicks in (slop).
+    pub pen_allowance: NormalizedCoefficient,
+    /// Fraction of the remaining penetration corrected per step.
+    pub percent_correction: NormalizedCoefficient,
+    /// Relative speed under which a contact is treated as resting and restitution is ignored.
+    ///
+    /// When `None`, the speed gained from gravity over one step is used.
+    pub restitution_threshold: Option<MeterPerSec>,
+}
+
+impl WorldSettings {
+    /// Returns the resting speed threshold for the given time step.
+    ///
+    /// # Arguments
+    ///
+    /// * `dt` - The time step.
+    pub fn rest_speed(&self, dt: f64) -> MeterPerSec {
+        self.restitution_threshold
+            .unwrap_or_else(|| (dt * self.gravity).norm())
+    }
+}
+
+impl Default for WorldSettings {
+    /// Returns the settings the engine has always used: downward gravity, ten velocity iterations
+    /// and the default positional correction.
+    fn default() -> Self {
+        WorldSettings {
+            gravity: GRAVITY,
+            velocity_iterations: VELOCITY_ITERATIONS,
+            pen_allowance: PEN_ALLOWANCE,
+            percent_correction: PERCENT_CORRECTION,
+            restitution_threshold: None,
+        }
+    }
+}